@@ -0,0 +1,222 @@
+//! Alphabets and codecs for writing geohash cells as strings.
+//!
+//! An Alphabet writes the bits of an integer geohash with as many bits per
+//! character as the power of two of its size, so the standard base32 alphabet
+//! gives the usual string geohashes and base16 and base4 give hex and
+//! quaternary strings of the same integers. Geohash36 is a different grid of 6
+//! by 6 cells per character. Both implement Codec, which the *_with_codec
+//! functions of the crate accept.
+
+use crate::{base32, bounding_box_int_with_precision, encode_int, shr, Box};
+
+/// Codec writes the cells of points as strings and reads them back.
+pub trait Codec {
+    /// max_chars returns the largest number of characters of a string.
+    fn max_chars(&self) -> usize;
+
+    /// encode returns the string with chars characters (max max_chars) of the
+    /// cell that holds the point (lat, lng).
+    fn encode(&self, lat: f64, lng: f64, chars: usize) -> String;
+
+    /// bounding_box returns the region of the string.
+    fn bounding_box(&self, hash: &str) -> Result<Box, String>;
+
+    /// validate the string.
+    fn validate(&self, hash: &str) -> Result<bool, String>;
+}
+
+/// Value of the characters that are not in an alphabet.
+const INVALID: u8 = 0xff;
+
+/// Alphabet is a set of 2, 4, 8, 16, 32 or 64 distinct ASCII characters, each
+/// standing for as many bits as the power of two of the size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alphabet {
+    symbols: [u8; 64],
+    size: usize,
+    bits: usize,
+    values: [u8; 256],
+}
+
+/// BASE32 is the alphabet of standard geohashes.
+pub const BASE32: Alphabet = Alphabet::build(&base32::BASE32_ENCODING);
+/// BASE16 writes integer geohashes as lower case hex digits.
+pub const BASE16: Alphabet = Alphabet::build(b"0123456789abcdef");
+/// BASE4 writes integer geohashes as quaternary digits, one per level of the
+/// quadtree.
+pub const BASE4: Alphabet = Alphabet::build(b"0123");
+
+impl Alphabet {
+    /// new returns the alphabet of the characters, which must be distinct
+    /// ASCII characters and as many as a power of two from 2 to 64.
+    pub fn new(symbols: &str) -> Result<Alphabet, String> {
+        let bytes = symbols.as_bytes();
+        if !bytes.len().is_power_of_two() || !(2..=64).contains(&bytes.len()) {
+            return Err(format!("invalid alphabet size {}", bytes.len()));
+        }
+        for (i, &c) in bytes.iter().enumerate() {
+            if !c.is_ascii() {
+                return Err(format!("invalid character {}", c));
+            }
+            if bytes[..i].contains(&c) {
+                return Err(format!("duplicate character {}", c as char));
+            }
+        }
+        Ok(Alphabet::build(bytes))
+    }
+
+    /// build returns the alphabet of the characters, which are known to be
+    /// valid, with the table of their values.
+    const fn build(symbols: &[u8]) -> Alphabet {
+        let mut alphabet = Alphabet {
+            symbols: [0; 64],
+            size: symbols.len(),
+            bits: symbols.len().trailing_zeros() as usize,
+            values: [INVALID; 256],
+        };
+        let mut i = 0;
+        while i < symbols.len() {
+            alphabet.symbols[i] = symbols[i];
+            alphabet.values[symbols[i] as usize] = i as u8;
+            i += 1;
+        }
+        alphabet
+    }
+
+    /// bits returns the number of bits of each character.
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// symbols returns the characters of the alphabet in the order of their
+    /// values.
+    pub fn symbols(&self) -> &[u8] {
+        &self.symbols[..self.size]
+    }
+
+    /// value returns the value of the character, if it is in the alphabet.
+    pub fn value(&self, c: u8) -> Option<u8> {
+        match self.values[c as usize] {
+            INVALID => None,
+            v => Some(v),
+        }
+    }
+
+    /// encode_int returns the string with chars characters of the integer
+    /// geohash with chars times bits bits of precision.
+    pub fn encode_int(&self, hash: u64, chars: usize) -> String {
+        let chars = chars.min(self.max_chars());
+        let mask = (self.size - 1) as u64;
+        (0..chars)
+            .rev()
+            .map(|i| self.symbols[(shr(hash, i * self.bits) & mask) as usize] as char)
+            .collect()
+    }
+
+    /// decode_int returns the integer geohash of the string, which has as many
+    /// bits of precision as the characters have bits.
+    pub fn decode_int(&self, hash: &str) -> Result<u64, String> {
+        self.validate(hash)?;
+        Ok(hash.bytes().fold(0, |x, c| {
+            crate::shl(x, self.bits) | self.values[c as usize] as u64
+        }))
+    }
+}
+
+impl Codec for Alphabet {
+    fn max_chars(&self) -> usize {
+        64 / self.bits
+    }
+
+    fn encode(&self, lat: f64, lng: f64, chars: usize) -> String {
+        let chars = chars.min(self.max_chars());
+        let hash = shr(encode_int(lat, lng), 64 - chars * self.bits);
+        self.encode_int(hash, chars)
+    }
+
+    fn bounding_box(&self, hash: &str) -> Result<Box, String> {
+        let inthash = self.decode_int(hash)?;
+        Ok(bounding_box_int_with_precision(
+            inthash,
+            hash.len() * self.bits,
+        ))
+    }
+
+    fn validate(&self, hash: &str) -> Result<bool, String> {
+        if hash.len() > self.max_chars() {
+            return Err("too long".to_owned());
+        }
+        for b in hash.bytes() {
+            if self.value(b).is_none() {
+                return Err(format!("invalid character {}", b));
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Geohash36 is the case-sensitive geohash-36 codec, where every character
+/// splits a cell into a grid of 6 by 6, from north-west to south-east.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Geohash36;
+
+/// GEOHASH36_ENCODING holds the characters of the grid of geohash-36, row by
+/// row from the north.
+const GEOHASH36_ENCODING: &[u8; 36] = b"23456789bBCdDFgGhHjJKlLMnNPqQrRtTVWX";
+/// Largest number of characters of a geohash-36 string.
+const GEOHASH36_MAX_CHARS: usize = 12;
+
+impl Codec for Geohash36 {
+    fn max_chars(&self) -> usize {
+        GEOHASH36_MAX_CHARS
+    }
+
+    fn encode(&self, lat: f64, lng: f64, chars: usize) -> String {
+        let chars = chars.min(GEOHASH36_MAX_CHARS);
+        let n = 6u64.pow(chars as u32);
+        let index = |x: f64, r: f64| {
+            let p = (x + r) / (2.0 * r) * n as f64;
+            (p.max(0.0) as u64).min(n - 1)
+        };
+        let mut row = n - 1 - index(lat, 90.0);
+        let mut column = index(lng, 180.0);
+        let mut hash = vec![0; chars];
+        for c in hash.iter_mut().rev() {
+            *c = GEOHASH36_ENCODING[(row % 6 * 6 + column % 6) as usize];
+            row /= 6;
+            column /= 6;
+        }
+        String::from_utf8(hash).unwrap()
+    }
+
+    fn bounding_box(&self, hash: &str) -> Result<Box, String> {
+        self.validate(hash)?;
+        let (mut row, mut column) = (0u64, 0u64);
+        for c in hash.bytes() {
+            let i = GEOHASH36_ENCODING.iter().position(|&s| s == c).unwrap() as u64;
+            row = row * 6 + i / 6;
+            column = column * 6 + i % 6;
+        }
+        let n = 6u64.pow(hash.len() as u32) as f64;
+        let max_lat = 90.0 - 180.0 * row as f64 / n;
+        let min_lng = -180.0 + 360.0 * column as f64 / n;
+        Ok(Box {
+            min_lat: max_lat - 180.0 / n,
+            max_lat,
+            min_lng,
+            max_lng: min_lng + 360.0 / n,
+        })
+    }
+
+    fn validate(&self, hash: &str) -> Result<bool, String> {
+        if hash.len() > GEOHASH36_MAX_CHARS {
+            return Err("too long".to_owned());
+        }
+        for b in hash.bytes() {
+            if !GEOHASH36_ENCODING.contains(&b) {
+                return Err(format!("invalid character {}", b));
+            }
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,221 @@
+//! 3D geohashes, which add an altitude axis over a configurable range to the
+//! latitude and longitude of a geohash.
+//!
+//! A full integer key holds 21 bits of each of the longitude, latitude and
+//! altitude, 63 bits in all, taking turns from the most significant bit. A key
+//! with bits of precision is the prefix of the full keys of every point in its
+//! cell, and strings hold 5 bits per character in the base32 alphabet of
+//! geohashes, so a string of 12 characters has 20 bits on each axis.
+
+use crate::{
+    axis_bits3, base32, base32_string, decode_extent, decode_range, encode_extent, encode_range,
+    morton, shl, shr, Box,
+};
+
+/// MAX_BITS is the number of bits of a full integer key.
+pub const MAX_BITS: usize = 63;
+/// Number of bits of each axis of a full key.
+const AXIS_BITS: usize = 21;
+
+/// AltitudeRange is the span of altitudes from min to max, in any unit such as
+/// meters, that keys can tell apart. Altitudes outside of it are clamped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AltitudeRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl AltitudeRange {
+    pub fn new(min: f64, max: f64) -> AltitudeRange {
+        AltitudeRange { min, max }
+    }
+}
+
+/// Box3d is a box in latitude/longitude space between two altitudes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Box3d {
+    pub space: Box,
+    pub min_alt: f64,
+    pub max_alt: f64,
+}
+
+impl Box3d {
+    /// center returns the point (lat, lng, alt) in the middle of the box.
+    pub fn center(&self) -> (f64, f64, f64) {
+        let (lat, lng) = self.space.center();
+        (lat, lng, (self.min_alt + self.max_alt) / 2.0)
+    }
+
+    /// contains decides whether the point (lat, lng, alt) lies within the box.
+    pub fn contains(&self, lat: f64, lng: f64, alt: f64) -> bool {
+        self.space.contains(lat, lng) && self.min_alt <= alt && alt <= self.max_alt
+    }
+}
+
+/// encode encodes the point (lat, lng, alt) to a string 3D geohash of 12
+/// characters.
+pub fn encode(lat: f64, lng: f64, alt: f64, range: &AltitudeRange) -> String {
+    encode_with_precision(lat, lng, alt, range, 12)
+}
+
+/// encode_with_precision encodes the point (lat, lng, alt) to a string 3D
+/// geohash with the specified number of characters (max 12).
+pub fn encode_with_precision(
+    lat: f64,
+    lng: f64,
+    alt: f64,
+    range: &AltitudeRange,
+    chars: usize,
+) -> String {
+    let chars = chars.min(12);
+    let hash = encode_int_with_precision(lat, lng, alt, range, 5 * chars);
+    base32_string(hash, chars)
+}
+
+/// encode_int encodes the point (lat, lng, alt) to a full integer key of
+/// MAX_BITS bits.
+pub fn encode_int(lat: f64, lng: f64, alt: f64, range: &AltitudeRange) -> u64 {
+    let shift = 32 - AXIS_BITS;
+    let lat_int = encode_range(lat, 90.0) >> shift;
+    let lng_int = encode_range(lng, 180.0) >> shift;
+    let alt_int = encode_extent(alt, range.min, range.max) >> shift;
+    morton::encode3(alt_int, lat_int, lng_int, AXIS_BITS)
+}
+
+/// encode_int_with_precision encodes the point (lat, lng, alt) to an integer
+/// key with the specified number of bits (max MAX_BITS).
+pub fn encode_int_with_precision(
+    lat: f64,
+    lng: f64,
+    alt: f64,
+    range: &AltitudeRange,
+    bits: usize,
+) -> u64 {
+    let bits = bits.min(MAX_BITS);
+    shr(encode_int(lat, lng, alt, range), MAX_BITS - bits)
+}
+
+/// bounding_box returns the region of the string 3D geohash.
+pub fn bounding_box(hash: &str, range: &AltitudeRange) -> Box3d {
+    let inthash = base32::decode(hash.as_bytes());
+    bounding_box_int_with_precision(inthash, 5 * hash.len(), range)
+}
+
+/// bounding_box_int_with_precision returns the region of the integer key with
+/// the specified precision.
+pub fn bounding_box_int_with_precision(hash: u64, bits: usize, range: &AltitudeRange) -> Box3d {
+    let bits = bits.min(MAX_BITS);
+    let (lng_bits, lat_bits, alt_bits) = axis_bits3(bits);
+    let (alt, lat, lng) = morton::decode3(shl(hash, MAX_BITS - bits), AXIS_BITS);
+    let shift = 32 - AXIS_BITS;
+    let min_lat = decode_range(lat << shift, 90.0);
+    let min_lng = decode_range(lng << shift, 180.0);
+    let min_alt = decode_extent(alt << shift, range.min, range.max);
+    Box3d {
+        space: Box {
+            min_lat,
+            max_lat: min_lat + 180.0 / (lat_bits as f64).exp2(),
+            min_lng,
+            max_lng: min_lng + 360.0 / (lng_bits as f64).exp2(),
+        },
+        min_alt,
+        max_alt: min_alt + (range.max - range.min) / (alt_bits as f64).exp2(),
+    }
+}
+
+/// decode returns the point (lat, lng, alt) at the center of the cell of the
+/// string 3D geohash.
+pub fn decode(hash: &str, range: &AltitudeRange) -> (f64, f64, f64) {
+    bounding_box(hash, range).center()
+}
+
+/// decode_int_with_precision returns the point (lat, lng, alt) at the center
+/// of the cell of the integer key with bits of precision.
+pub fn decode_int_with_precision(hash: u64, bits: usize, range: &AltitudeRange) -> (f64, f64, f64) {
+    bounding_box_int_with_precision(hash, bits, range).center()
+}
+
+/// neighbors returns the string 3D geohashes of the cells around the cell of
+/// the hash, in the order of neighbors_int_with_precision.
+pub fn neighbors(hash: &str) -> Vec<String> {
+    let chars = hash.len();
+    neighbors_int_with_precision(base32::decode(hash.as_bytes()), 5 * chars)
+        .into_iter()
+        .map(|n| base32_string(n, chars))
+        .collect()
+}
+
+/// neighbors_int_with_precision returns the keys of the up to 26 cells that
+/// share a face, edge or corner with the cell of the key, ordered by altitude,
+/// then latitude, then longitude, each from low to high. Longitudes wrap
+/// around the antimeridian, while cells beyond the poles and the ends of the
+/// altitude range are left out.
+pub fn neighbors_int_with_precision(hash: u64, bits: usize) -> Vec<u64> {
+    let bits = bits.min(MAX_BITS);
+    let (lng_bits, lat_bits, alt_bits) = axis_bits3(bits);
+    let (alt, lat, lng) = morton::decode3(shl(hash, MAX_BITS - bits), AXIS_BITS);
+    let (alt, lat, lng) = (
+        alt as u64 >> (AXIS_BITS - alt_bits),
+        lat as u64 >> (AXIS_BITS - lat_bits),
+        lng as u64 >> (AXIS_BITS - lng_bits),
+    );
+    let mut neighbors = Vec::with_capacity(26);
+    for dalt in -1..=1i64 {
+        for dlat in -1..=1i64 {
+            for dlng in -1..=1i64 {
+                if dalt == 0 && dlat == 0 && dlng == 0 {
+                    continue;
+                }
+                let a = alt as i64 + dalt;
+                let y = lat as i64 + dlat;
+                if a < 0 || a >> alt_bits != 0 || y < 0 || y >> lat_bits != 0 {
+                    continue;
+                }
+                let x = (lng as i64 + dlng).rem_euclid(1 << lng_bits);
+                let key = morton::encode3(
+                    (a << (AXIS_BITS - alt_bits)) as u32,
+                    (y << (AXIS_BITS - lat_bits)) as u32,
+                    (x << (AXIS_BITS - lng_bits)) as u32,
+                    AXIS_BITS,
+                );
+                // Narrow cells wrap around to themselves or to the same
+                // neighbor on both sides.
+                let key = shr(key, MAX_BITS - bits);
+                if key != hash && !neighbors.contains(&key) {
+                    neighbors.push(key);
+                }
+            }
+        }
+    }
+    neighbors
+}
+
+/// parent returns the string 3D geohash one character shorter than the hash.
+pub fn parent(hash: &str) -> String {
+    hash[..hash.len().saturating_sub(1)].to_owned()
+}
+
+/// children returns the 32 string 3D geohashes one character longer than the
+/// hash.
+pub fn children(hash: &str) -> Vec<String> {
+    base32::BASE32_ENCODING
+        .iter()
+        .map(|&c| format!("{}{}", hash, c as char))
+        .collect()
+}
+
+/// parent_int returns the key, with bits - 3 bits of precision, of the cell
+/// twice as large along each axis that holds the cell of the key.
+pub fn parent_int(hash: u64, bits: usize) -> u64 {
+    shr(hash, bits.min(3))
+}
+
+/// children_int returns the keys, with 3 more bits of precision, of the eight
+/// cells half as large along each axis within the cell of the key.
+pub fn children_int(hash: u64) -> [u64; 8] {
+    let mut children = [0; 8];
+    for (i, child) in children.iter_mut().enumerate() {
+        *child = hash << 3 | i as u64;
+    }
+    children
+}
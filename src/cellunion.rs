@@ -0,0 +1,338 @@
+//! Normalized sets of mixed-precision geohash cells.
+
+use crate::{
+    base32, base32_string, bounding_box_int_with_precision, cell_key_range, encode_int, low_mask64,
+    merge_ranges,
+};
+
+/// CellUnion is a normalized set of string geohash cells of mixed precision.
+/// Cells contained in other cells are dropped, and complete groups of 32
+/// sibling cells are merged into their parent, so that two unions covering the
+/// same region hold the same cells.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CellUnion {
+    cells: Vec<(u64, usize)>,
+}
+
+impl CellUnion {
+    /// new returns the normalized union of the given string geohashes. Hashes
+    /// longer than 12 characters are cut to their first 12.
+    pub fn new<S: AsRef<str>>(hashes: &[S]) -> CellUnion {
+        let cells: Vec<(u64, usize)> = hashes
+            .iter()
+            .map(|hash| string_cell(hash.as_ref()))
+            .collect();
+        CellUnion {
+            cells: normalize(&cells, 5),
+        }
+    }
+
+    /// cells returns the string geohashes of the union, ordered by their
+    /// position on the Z-order curve.
+    pub fn cells(&self) -> Vec<String> {
+        self.iter().collect()
+    }
+
+    /// iter returns an iterator over the string geohashes of the union, ordered
+    /// by their position on the Z-order curve.
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        self.cells
+            .iter()
+            .map(|&(hash, bits)| base32_string(hash, bits / 5))
+    }
+
+    /// len returns the number of cells in the union.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// is_empty decides whether the union holds no cells.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// union returns the cells that are in either union.
+    pub fn union(&self, other: &CellUnion) -> CellUnion {
+        let mut cells = self.cells.clone();
+        cells.extend_from_slice(&other.cells);
+        CellUnion {
+            cells: normalize(&cells, 5),
+        }
+    }
+
+    /// intersection returns the region that is in both unions.
+    pub fn intersection(&self, other: &CellUnion) -> CellUnion {
+        let ranges = intersect_ranges(&to_ranges(&self.cells), &to_ranges(&other.cells));
+        CellUnion {
+            cells: from_ranges(&ranges, 5),
+        }
+    }
+
+    /// difference returns the region that is in this union but not the other.
+    pub fn difference(&self, other: &CellUnion) -> CellUnion {
+        let ranges = subtract_ranges(&to_ranges(&self.cells), &to_ranges(&other.cells));
+        CellUnion {
+            cells: from_ranges(&ranges, 5),
+        }
+    }
+
+    /// contains_point decides whether the point (lat, lng) lies in the union.
+    pub fn contains_point(&self, lat: f64, lng: f64) -> bool {
+        contains_key(&self.cells, encode_int(lat, lng))
+    }
+
+    /// contains_cell decides whether the cell of the string geohash lies
+    /// completely within the union.
+    pub fn contains_cell(&self, hash: &str) -> bool {
+        let (hash, bits) = string_cell(hash);
+        contains_cell(&self.cells, hash, bits)
+    }
+
+    /// intersects_cell decides whether the cell of the string geohash and the
+    /// union have any area in common.
+    pub fn intersects_cell(&self, hash: &str) -> bool {
+        let (hash, bits) = string_cell(hash);
+        intersects_cell(&self.cells, hash, bits)
+    }
+
+    /// area returns the area of the union in square degrees.
+    pub fn area(&self) -> f64 {
+        area(&self.cells)
+    }
+
+    /// to_int returns the union as integer geohash cells.
+    pub fn to_int(&self) -> IntCellUnion {
+        IntCellUnion::new(&self.cells)
+    }
+}
+
+/// IntCellUnion is a normalized set of integer geohash cells of mixed
+/// precision, each given as (hash, bits). Cells contained in other cells are
+/// dropped, and complete pairs of sibling cells are merged into their parent.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntCellUnion {
+    cells: Vec<(u64, usize)>,
+}
+
+impl IntCellUnion {
+    /// new returns the normalized union of the given integer geohashes, each
+    /// given as (hash, bits). Precisions above 64 bits are taken as 64, and
+    /// hash bits above the precision are ignored.
+    pub fn new(cells: &[(u64, usize)]) -> IntCellUnion {
+        let cells: Vec<(u64, usize)> = cells
+            .iter()
+            .map(|&(hash, bits)| int_cell(hash, bits))
+            .collect();
+        IntCellUnion {
+            cells: normalize(&cells, 1),
+        }
+    }
+
+    /// cells returns the integer geohashes of the union as (hash, bits),
+    /// ordered by their position on the Z-order curve.
+    pub fn cells(&self) -> &[(u64, usize)] {
+        &self.cells
+    }
+
+    /// iter returns an iterator over the integer geohashes of the union as
+    /// (hash, bits), ordered by their position on the Z-order curve.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, usize)> + '_ {
+        self.cells.iter().copied()
+    }
+
+    /// len returns the number of cells in the union.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// is_empty decides whether the union holds no cells.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// union returns the cells that are in either union.
+    pub fn union(&self, other: &IntCellUnion) -> IntCellUnion {
+        let mut cells = self.cells.clone();
+        cells.extend_from_slice(&other.cells);
+        IntCellUnion::new(&cells)
+    }
+
+    /// intersection returns the region that is in both unions.
+    pub fn intersection(&self, other: &IntCellUnion) -> IntCellUnion {
+        let ranges = intersect_ranges(&to_ranges(&self.cells), &to_ranges(&other.cells));
+        IntCellUnion {
+            cells: from_ranges(&ranges, 1),
+        }
+    }
+
+    /// difference returns the region that is in this union but not the other.
+    pub fn difference(&self, other: &IntCellUnion) -> IntCellUnion {
+        let ranges = subtract_ranges(&to_ranges(&self.cells), &to_ranges(&other.cells));
+        IntCellUnion {
+            cells: from_ranges(&ranges, 1),
+        }
+    }
+
+    /// contains_point decides whether the point (lat, lng) lies in the union.
+    pub fn contains_point(&self, lat: f64, lng: f64) -> bool {
+        contains_key(&self.cells, encode_int(lat, lng))
+    }
+
+    /// contains_cell decides whether the cell of the integer geohash with bits
+    /// of precision lies completely within the union.
+    pub fn contains_cell(&self, hash: u64, bits: usize) -> bool {
+        let (hash, bits) = int_cell(hash, bits);
+        contains_cell(&self.cells, hash, bits)
+    }
+
+    /// intersects_cell decides whether the cell of the integer geohash with
+    /// bits of precision and the union have any area in common.
+    pub fn intersects_cell(&self, hash: u64, bits: usize) -> bool {
+        let (hash, bits) = int_cell(hash, bits);
+        intersects_cell(&self.cells, hash, bits)
+    }
+
+    /// area returns the area of the union in square degrees.
+    pub fn area(&self) -> f64 {
+        area(&self.cells)
+    }
+}
+
+/// normalize returns the canonical cells covering the same region as the given
+/// cells, using only precisions that are a multiple of step bits.
+fn normalize(cells: &[(u64, usize)], step: usize) -> Vec<(u64, usize)> {
+    from_ranges(&to_ranges(cells), step)
+}
+
+/// to_ranges returns the sorted and merged [start, end) ranges of 64-bit
+/// integer geohashes covered by the cells.
+fn to_ranges(cells: &[(u64, usize)]) -> Vec<(u128, u128)> {
+    let ranges = cells
+        .iter()
+        .map(|&(hash, bits)| cell_key_range(hash, bits))
+        .collect();
+    merge_ranges(ranges, usize::MAX)
+}
+
+/// from_ranges decomposes the ranges into the fewest cells, using only
+/// precisions that are a multiple of step bits. Range boundaries must be
+/// aligned to such cells.
+fn from_ranges(ranges: &[(u128, u128)], step: usize) -> Vec<(u64, usize)> {
+    let mut cells = Vec::new();
+    for &(start, end) in ranges {
+        let mut pos = start;
+        while pos < end {
+            let mut bits = 0;
+            loop {
+                let size = 1u128 << (64 - bits);
+                if pos % size == 0 && pos + size <= end {
+                    cells.push(((pos >> (64 - bits)) as u64, bits));
+                    pos += size;
+                    break;
+                }
+                bits += step;
+            }
+        }
+    }
+    cells
+}
+
+/// intersect_ranges returns the intersection of two sorted lists of disjoint
+/// ranges.
+fn intersect_ranges(a: &[(u128, u128)], b: &[(u128, u128)]) -> Vec<(u128, u128)> {
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let start = a[i].0.max(b[j].0);
+        let end = a[i].1.min(b[j].1);
+        if start < end {
+            result.push((start, end));
+        }
+        if a[i].1 < b[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    result
+}
+
+/// subtract_ranges returns the parts of the ranges in a that are not in b. Both
+/// must be sorted lists of disjoint ranges.
+fn subtract_ranges(a: &[(u128, u128)], b: &[(u128, u128)]) -> Vec<(u128, u128)> {
+    let mut result = Vec::new();
+    let mut j = 0;
+    for &(start, end) in a {
+        let mut pos = start;
+        while j < b.len() && b[j].1 <= pos {
+            j += 1;
+        }
+        let mut k = j;
+        while k < b.len() && b[k].0 < end {
+            if pos < b[k].0 {
+                result.push((pos, b[k].0));
+            }
+            pos = pos.max(b[k].1);
+            k += 1;
+        }
+        if pos < end {
+            result.push((pos, end));
+        }
+    }
+    result
+}
+
+/// find returns the index of the last cell starting at or before key, if any.
+fn find(cells: &[(u64, usize)], key: u128) -> Option<usize> {
+    let i = cells.partition_point(|&(hash, bits)| cell_key_range(hash, bits).0 <= key);
+    i.checked_sub(1)
+}
+
+fn contains_key(cells: &[(u64, usize)], key: u64) -> bool {
+    match find(cells, key as u128) {
+        Some(i) => (key as u128) < cell_key_range(cells[i].0, cells[i].1).1,
+        None => false,
+    }
+}
+
+fn contains_cell(cells: &[(u64, usize)], hash: u64, bits: usize) -> bool {
+    let (start, end) = cell_key_range(hash, bits);
+    match find(cells, start) {
+        Some(i) => end <= cell_key_range(cells[i].0, cells[i].1).1,
+        None => false,
+    }
+}
+
+fn intersects_cell(cells: &[(u64, usize)], hash: u64, bits: usize) -> bool {
+    let (start, end) = cell_key_range(hash, bits);
+    if contains_key(cells, start as u64) {
+        return true;
+    }
+    // Otherwise the first cell after the start must begin inside the cell.
+    let i = cells.partition_point(|&(hash, bits)| cell_key_range(hash, bits).0 <= start);
+    i < cells.len() && cell_key_range(cells[i].0, cells[i].1).0 < end
+}
+
+fn area(cells: &[(u64, usize)]) -> f64 {
+    cells
+        .iter()
+        .map(|&(hash, bits)| {
+            let b = bounding_box_int_with_precision(hash, bits);
+            (b.max_lat - b.min_lat) * (b.max_lng - b.min_lng)
+        })
+        .sum()
+}
+
+/// int_cell returns the integer geohash and precision (hash, bits) of the cell,
+/// with the precision cut to 64 bits and the hash bits above it cleared.
+fn int_cell(hash: u64, bits: usize) -> (u64, usize) {
+    let bits = bits.min(64);
+    (hash & low_mask64(bits), bits)
+}
+
+/// string_cell returns the integer geohash and precision (hash, bits) of the
+/// string geohash, cut to the 12 characters that a 64-bit integer can hold.
+fn string_cell(hash: &str) -> (u64, usize) {
+    let bytes = &hash.as_bytes()[..hash.len().min(12)];
+    (base32::decode(bytes), 5 * bytes.len())
+}
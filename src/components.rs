@@ -0,0 +1,272 @@
+//! Connected components of sets of geohash cells.
+
+use crate::topology::{relation_int, CellRelation};
+use crate::{base32, bounding_box_int_with_precision, cell_int_range, interleave, shr, Box};
+use std::collections::HashMap;
+
+/// Connectivity decides which cells that touch belong to the same component.
+/// Cells that overlap always do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Four connects cells that share part of an edge.
+    Four,
+    /// Eight connects cells that share part of an edge or a corner.
+    Eight,
+}
+
+/// Component is a group of connected string geohash cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Component {
+    /// cells are the geohashes of the group, in the order they were given.
+    pub cells: Vec<String>,
+    /// bounding_box is the smallest box around the cells. It crosses the
+    /// antimeridian, with min_lng > max_lng, when that makes it narrower.
+    pub bounding_box: Box,
+    /// count is the number of cells in the group.
+    pub count: usize,
+}
+
+/// IntComponent is a group of connected integer geohash cells, each given as
+/// (hash, bits).
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntComponent {
+    /// cells are the geohashes of the group, in the order they were given.
+    pub cells: Vec<(u64, usize)>,
+    /// bounding_box is the smallest box around the cells.
+    pub bounding_box: Box,
+    /// count is the number of cells in the group.
+    pub count: usize,
+}
+
+/// connected_components splits the string geohashes into groups of cells that
+/// are connected, directly or through other cells of the set. Cells may have
+/// any precision, and cells on either side of the antimeridian touch.
+/// Components are ordered by their first cell.
+pub fn connected_components<S: AsRef<str>>(
+    hashes: &[S],
+    connectivity: Connectivity,
+) -> Vec<Component> {
+    let cells: Vec<(u64, usize)> = hashes
+        .iter()
+        .map(|hash| {
+            let hash = hash.as_ref();
+            (base32::decode(hash.as_bytes()), 5 * hash.len())
+        })
+        .collect();
+    groups(&cells, connectivity)
+        .into_iter()
+        .map(|group| Component {
+            cells: group
+                .iter()
+                .map(|&i| hashes[i].as_ref().to_owned())
+                .collect(),
+            bounding_box: group_box(&cells, &group),
+            count: group.len(),
+        })
+        .collect()
+}
+
+/// connected_components_int splits the integer geohashes, each given as (hash,
+/// bits), into groups of cells that are connected.
+pub fn connected_components_int(
+    cells: &[(u64, usize)],
+    connectivity: Connectivity,
+) -> Vec<IntComponent> {
+    groups(cells, connectivity)
+        .into_iter()
+        .map(|group| IntComponent {
+            cells: group.iter().map(|&i| cells[i]).collect(),
+            bounding_box: group_box(cells, &group),
+            count: group.len(),
+        })
+        .collect()
+}
+
+/// groups returns the indexes of the cells in each component.
+fn groups(cells: &[(u64, usize)], connectivity: Connectivity) -> Vec<Vec<usize>> {
+    let index = Index::new(cells);
+    let mut sets = DisjointSets::new(cells.len());
+    for (i, &(hash, bits)) in cells.iter().enumerate() {
+        // Every cell touching or overlapping this one overlaps it or one of
+        // its neighbors of the same size.
+        let mut candidates = vec![hash];
+        candidates.extend(grid_neighbors(hash, bits));
+        for candidate in candidates {
+            for j in index.overlapping(candidate, bits) {
+                if j != i && connected(cells[i], cells[j], connectivity) {
+                    sets.union(i, j);
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of: HashMap<usize, usize> = HashMap::new();
+    for i in 0..cells.len() {
+        let root = sets.find(i);
+        let g = *group_of.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[g].push(i);
+    }
+    groups
+}
+
+fn connected(a: (u64, usize), b: (u64, usize), connectivity: Connectivity) -> bool {
+    match relation_int(a.0, a.1, b.0, b.1) {
+        CellRelation::Disjoint => false,
+        CellRelation::SharesCorner => connectivity == Connectivity::Eight,
+        _ => true,
+    }
+}
+
+/// grid_neighbors returns the cells of the same size around the cell, wrapping
+/// around the antimeridian. Cells at the poles have fewer neighbors.
+fn grid_neighbors(hash: u64, bits: usize) -> Vec<u64> {
+    let (lat, lng) = cell_int_range(hash, bits);
+    let lat_size = lat.1 as i64 - lat.0 as i64 + 1;
+    let lng_size = lng.1 as i64 - lng.0 as i64 + 1;
+    let mut neighbors = Vec::with_capacity(8);
+    for dlat in -1..=1 {
+        for dlng in -1..=1 {
+            if dlat == 0 && dlng == 0 {
+                continue;
+            }
+            let y = lat.0 as i64 + dlat * lat_size;
+            if !(0..1 << 32).contains(&y) {
+                continue;
+            }
+            let x = (lng.0 as i64 + dlng * lng_size).rem_euclid(1 << 32);
+            neighbors.push(shr(interleave(y as u32, x as u32), 64 - bits));
+        }
+    }
+    neighbors
+}
+
+/// Index finds the cells of a set that overlap a given cell.
+struct Index {
+    /// by_cell maps each distinct cell to the indexes of its copies.
+    by_cell: HashMap<(u64, usize), Vec<usize>>,
+    /// precisions are the distinct precisions of the cells, ascending.
+    precisions: Vec<usize>,
+    /// sorted holds the position of every cell on the 64-bit curve with its
+    /// precision and index, in curve order.
+    sorted: Vec<(u64, usize, usize)>,
+}
+
+impl Index {
+    fn new(cells: &[(u64, usize)]) -> Index {
+        let mut by_cell: HashMap<(u64, usize), Vec<usize>> = HashMap::new();
+        let mut sorted = Vec::with_capacity(cells.len());
+        for (i, &(hash, bits)) in cells.iter().enumerate() {
+            by_cell.entry((hash, bits)).or_default().push(i);
+            sorted.push((crate::shl(hash, 64 - bits), bits, i));
+        }
+        sorted.sort_unstable();
+        let mut precisions: Vec<usize> = cells.iter().map(|&(_, bits)| bits).collect();
+        precisions.sort_unstable();
+        precisions.dedup();
+        Index {
+            by_cell,
+            precisions,
+            sorted,
+        }
+    }
+
+    /// overlapping returns the indexes of the cells that contain, equal or lie
+    /// within the cell.
+    fn overlapping(&self, hash: u64, bits: usize) -> Vec<usize> {
+        let mut result = Vec::new();
+        for &p in self.precisions.iter().take_while(|&&p| p <= bits) {
+            if let Some(copies) = self.by_cell.get(&(shr(hash, bits - p), p)) {
+                result.extend(copies);
+            }
+        }
+        let start = crate::shl(hash, 64 - bits);
+        let end = start | crate::low_mask64(64 - bits);
+        let first = self.sorted.partition_point(|&(key, _, _)| key < start);
+        for &(key, p, i) in &self.sorted[first..] {
+            if key > end {
+                break;
+            }
+            if p > bits {
+                result.push(i);
+            }
+        }
+        result
+    }
+}
+
+/// DisjointSets is a union-find structure over the indexes 0..n.
+struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> DisjointSets {
+        DisjointSets {
+            parent: (0..n).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[a.max(b)] = a.min(b);
+        }
+    }
+}
+
+/// group_box returns the smallest box around the cells of the group. The
+/// longitudes are wrapped around the largest gap between the cells.
+fn group_box(cells: &[(u64, usize)], group: &[usize]) -> Box {
+    let boxes: Vec<Box> = group
+        .iter()
+        .map(|&i| bounding_box_int_with_precision(cells[i].0, cells[i].1))
+        .collect();
+    let min_lat = boxes
+        .iter()
+        .map(|b| b.min_lat)
+        .fold(f64::INFINITY, f64::min);
+    let max_lat = boxes
+        .iter()
+        .map(|b| b.max_lat)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    let mut spans: Vec<(f64, f64)> = boxes.iter().map(|b| (b.min_lng, b.max_lng)).collect();
+    spans.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mut merged: Vec<(f64, f64)> = Vec::new();
+    for (lo, hi) in spans {
+        match merged.last_mut() {
+            Some(last) if lo <= last.1 => last.1 = last.1.max(hi),
+            _ => merged.push((lo, hi)),
+        }
+    }
+    // The gap from the last span around the antimeridian to the first.
+    let first = merged[0];
+    let last = merged[merged.len() - 1];
+    let (mut min_lng, mut max_lng) = (first.0, last.1);
+    let mut gap = first.0 + 360.0 - last.1;
+    for pair in merged.windows(2) {
+        if pair[1].0 - pair[0].1 > gap {
+            gap = pair[1].0 - pair[0].1;
+            min_lng = pair[1].0;
+            max_lng = pair[0].1;
+        }
+    }
+    Box {
+        min_lat,
+        max_lat,
+        min_lng,
+        max_lng,
+    }
+}
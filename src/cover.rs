@@ -0,0 +1,366 @@
+//! Coverings of regions by string geohash cells.
+
+use crate::distance::{max_distance_with_model, min_distance_with_model, DistanceModel};
+use crate::point::LatLng;
+use crate::{base32, bounding_box, Box};
+use std::collections::VecDeque;
+
+/// Region is an area in latitude/longitude space that can be covered by
+/// geohash cells.
+pub trait Region {
+    /// bounding_box returns a box containing the whole region.
+    fn bounding_box(&self) -> Box;
+
+    /// contains_box decides whether the box lies completely within the region.
+    /// It may return false for boxes that touch the boundary of the region.
+    fn contains_box(&self, b: &Box) -> bool;
+
+    /// intersects_box decides whether the box and the region may have any
+    /// point in common. It may return true for boxes that only come close.
+    fn intersects_box(&self, b: &Box) -> bool;
+}
+
+impl Region for Box {
+    fn bounding_box(&self) -> Box {
+        *self
+    }
+
+    fn contains_box(&self, b: &Box) -> bool {
+        Box::contains_box(self, b)
+    }
+
+    fn intersects_box(&self, b: &Box) -> bool {
+        self.intersects(b)
+    }
+}
+
+/// Circle is the region within radius meters of the point (lat, lng), with
+/// distances measured by the model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub lat: f64,
+    pub lng: f64,
+    pub radius: f64,
+    pub model: DistanceModel,
+}
+
+impl Circle {
+    /// new returns the circle with radius meters around (lat, lng) on the
+    /// default spherical earth.
+    pub fn new(lat: f64, lng: f64, radius: f64) -> Circle {
+        Circle::with_model(lat, lng, radius, DistanceModel::default())
+    }
+
+    /// with_model returns the circle with radius meters around (lat, lng),
+    /// with distances measured by the model.
+    pub fn with_model(lat: f64, lng: f64, radius: f64, model: DistanceModel) -> Circle {
+        Circle {
+            lat,
+            lng,
+            radius,
+            model,
+        }
+    }
+
+    /// around returns the circle with radius meters around the point on the
+    /// default spherical earth.
+    pub fn around<P: LatLng>(center: P, radius: f64) -> Circle {
+        Circle::new(center.lat(), center.lng(), radius)
+    }
+}
+
+impl Region for Circle {
+    fn bounding_box(&self) -> Box {
+        let r = self.radius / self.model.min_radius();
+        let d = r.to_degrees();
+        let min_lat = self.lat - d;
+        let max_lat = self.lat + d;
+        if min_lat <= -90.0 || max_lat >= 90.0 {
+            // The circle holds a pole and every longitude.
+            return Box {
+                min_lat: min_lat.max(-90.0),
+                max_lat: max_lat.min(90.0),
+                min_lng: -180.0,
+                max_lng: 180.0,
+            };
+        }
+        let dlng = (r.sin() / self.lat.to_radians().cos())
+            .min(1.0)
+            .asin()
+            .to_degrees();
+        let (mut min_lng, mut max_lng) = (self.lng - dlng, self.lng + dlng);
+        if min_lng < -180.0 || max_lng > 180.0 {
+            min_lng = -180.0;
+            max_lng = 180.0;
+        }
+        Box {
+            min_lat,
+            max_lat,
+            min_lng,
+            max_lng,
+        }
+    }
+
+    fn contains_box(&self, b: &Box) -> bool {
+        max_distance_with_model(self.lat, self.lng, b, &self.model) <= self.radius
+    }
+
+    fn intersects_box(&self, b: &Box) -> bool {
+        min_distance_with_model(self.lat, self.lng, b, &self.model) <= self.radius
+    }
+}
+
+/// Polygon is the region inside the exterior ring and outside of the holes.
+/// Rings are lists of (lat, lng) vertices, and their edges are straight lines
+/// in latitude/longitude space.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Polygon {
+    pub exterior: Vec<(f64, f64)>,
+    pub holes: Vec<Vec<(f64, f64)>>,
+}
+
+impl Polygon {
+    /// new returns a polygon with the given exterior ring and no holes.
+    pub fn new(exterior: Vec<(f64, f64)>) -> Polygon {
+        Polygon {
+            exterior,
+            holes: Vec::new(),
+        }
+    }
+
+    /// from_points returns a polygon with the exterior ring of the points and
+    /// no holes.
+    pub fn from_points<P: LatLng, I: IntoIterator<Item = P>>(exterior: I) -> Polygon {
+        Polygon::new(exterior.into_iter().map(|p| (p.lat(), p.lng())).collect())
+    }
+
+    /// contains_point decides whether the point lies inside the polygon.
+    pub fn contains_point<P: LatLng>(&self, p: P) -> bool {
+        self.contains(p.lat(), p.lng())
+    }
+
+    /// contains decides whether (lat, lng) lies inside the polygon.
+    pub fn contains(&self, lat: f64, lng: f64) -> bool {
+        self.rings()
+            .filter(|ring| ring_contains(ring, lat, lng))
+            .count()
+            % 2
+            == 1
+    }
+
+    fn rings(&self) -> impl Iterator<Item = &Vec<(f64, f64)>> {
+        std::iter::once(&self.exterior).chain(self.holes.iter())
+    }
+
+    fn edges(&self) -> impl Iterator<Item = ((f64, f64), (f64, f64))> + '_ {
+        self.rings()
+            .flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+    }
+}
+
+impl Region for Polygon {
+    fn bounding_box(&self) -> Box {
+        let mut b = Box {
+            min_lat: f64::INFINITY,
+            max_lat: f64::NEG_INFINITY,
+            min_lng: f64::INFINITY,
+            max_lng: f64::NEG_INFINITY,
+        };
+        for &(lat, lng) in &self.exterior {
+            b.min_lat = b.min_lat.min(lat);
+            b.max_lat = b.max_lat.max(lat);
+            b.min_lng = b.min_lng.min(lng);
+            b.max_lng = b.max_lng.max(lng);
+        }
+        b
+    }
+
+    fn contains_box(&self, b: &Box) -> bool {
+        corners(b).iter().all(|&(lat, lng)| self.contains(lat, lng))
+            && !self.edges().any(|(p, q)| segment_intersects_box(p, q, b))
+    }
+
+    fn intersects_box(&self, b: &Box) -> bool {
+        corners(b).iter().any(|&(lat, lng)| self.contains(lat, lng))
+            || self.edges().any(|(p, q)| segment_intersects_box(p, q, b))
+    }
+}
+
+/// CoverOptions controls the cells of a covering. Levels are the number of
+/// characters of the string geohashes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoverOptions {
+    /// min_level is the smallest number of characters of any cell.
+    pub min_level: usize,
+    /// max_level is the largest number of characters of any cell (max 12).
+    pub max_level: usize,
+    /// max_cells is the desired maximum number of cells. A covering may exceed
+    /// it when min_level requires more cells.
+    pub max_cells: usize,
+}
+
+impl Default for CoverOptions {
+    fn default() -> CoverOptions {
+        CoverOptions {
+            min_level: 1,
+            max_level: 12,
+            max_cells: 8,
+        }
+    }
+}
+
+/// cover returns string geohash cells that together cover the whole region.
+/// Large cells are refined into smaller ones for as long as the covering
+/// stays within the limits of the options.
+pub fn cover<R: Region + ?Sized>(region: &R, options: &CoverOptions) -> Vec<String> {
+    let max_level = options.max_level.min(12);
+    let min_level = options.min_level.min(max_level);
+
+    // Candidates are processed in level order, so the largest cells are
+    // refined first.
+    let mut result = Vec::new();
+    let mut queue: VecDeque<String> = VecDeque::new();
+    queue.push_back(String::new());
+    while let Some(hash) = queue.pop_front() {
+        let b = bounding_box(&hash);
+        if hash.len() >= min_level && (hash.len() >= max_level || region.contains_box(&b)) {
+            result.push(hash);
+            continue;
+        }
+        let children: Vec<String> = children(&hash)
+            .filter(|child| region.intersects_box(&bounding_box(child)))
+            .collect();
+        let count = result.len() + queue.len() + children.len();
+        if hash.len() >= min_level && count > options.max_cells {
+            result.push(hash);
+            result.extend(queue.drain(..));
+            break;
+        }
+        queue.extend(children);
+    }
+    result.sort();
+    result
+}
+
+/// MAX_EXPLORED is the largest number of cells interior_cover tests against
+/// a region. Regions with a long boundary stop being refined once the cells
+/// along it would exceed it.
+const MAX_EXPLORED: usize = 1 << 16;
+
+/// interior_cover returns string geohash cells that lie completely within the
+/// region. Cells that only intersect the region are left out, so the cells may
+/// not cover all of it. At most max_cells cells are returned. The cells are
+/// taken level by level from the largest, and when the cells of a level would
+/// exceed max_cells, those nearest to the center of the region are taken.
+pub fn interior_cover<R: Region + ?Sized>(region: &R, options: &CoverOptions) -> Vec<String> {
+    let max_level = options.max_level.min(12);
+    let min_level = options.min_level.min(max_level);
+
+    // A region without area holds no cell.
+    let bounds = region.bounding_box();
+    if !(bounds.min_lat < bounds.max_lat && bounds.min_lng < bounds.max_lng) {
+        return Vec::new();
+    }
+
+    let mut result = Vec::new();
+    let mut candidates = vec![String::new()];
+    let mut explored = 0;
+    for level in 0..=max_level {
+        let mut interior = Vec::new();
+        let mut boundary = Vec::new();
+        for hash in candidates {
+            let b = bounding_box(&hash);
+            if level >= min_level && region.contains_box(&b) {
+                interior.push(hash);
+            } else if level < max_level && region.intersects_box(&b) {
+                boundary.push(hash);
+            }
+        }
+        if result.len() + interior.len() > options.max_cells {
+            // Keep the cells nearest to the center of the region.
+            let (lat, lng) = bounds.center();
+            let distance = |hash: &String| {
+                let (clat, clng) = bounding_box(hash).center();
+                (clat - lat).powi(2) + (clng - lng).powi(2)
+            };
+            interior.sort_by(|a, b| distance(a).partial_cmp(&distance(b)).unwrap());
+            interior.truncate(options.max_cells - result.len());
+            result.extend(interior);
+            break;
+        }
+        result.extend(interior);
+        explored += 32 * boundary.len();
+        if boundary.is_empty() || result.len() == options.max_cells || explored > MAX_EXPLORED {
+            break;
+        }
+        candidates = boundary.iter().flat_map(|hash| children(hash)).collect();
+    }
+    result.sort();
+    result
+}
+
+/// children returns the 32 string geohashes one level below the hash.
+fn children(hash: &str) -> impl Iterator<Item = String> + '_ {
+    base32::BASE32_ENCODING.iter().map(move |&c| {
+        let mut child = String::with_capacity(hash.len() + 1);
+        child.push_str(hash);
+        child.push(c as char);
+        child
+    })
+}
+
+fn corners(b: &Box) -> [(f64, f64); 4] {
+    [
+        (b.min_lat, b.min_lng),
+        (b.min_lat, b.max_lng),
+        (b.max_lat, b.max_lng),
+        (b.max_lat, b.min_lng),
+    ]
+}
+
+/// ring_contains decides whether (lat, lng) lies inside the ring, using the
+/// even-odd rule.
+fn ring_contains(ring: &[(f64, f64)], lat: f64, lng: f64) -> bool {
+    let mut inside = false;
+    let mut j = ring.len().wrapping_sub(1);
+    for i in 0..ring.len() {
+        let (lat_i, lng_i) = ring[i];
+        let (lat_j, lng_j) = ring[j];
+        if (lat_i > lat) != (lat_j > lat)
+            && lng < (lng_j - lng_i) * (lat - lat_i) / (lat_j - lat_i) + lng_i
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// segment_intersects_box decides whether the segment from p to q has any
+/// point in common with the box, using Liang-Barsky clipping.
+fn segment_intersects_box(p: (f64, f64), q: (f64, f64), b: &Box) -> bool {
+    let (dlat, dlng) = (q.0 - p.0, q.1 - p.1);
+    let mut t0: f64 = 0.0;
+    let mut t1: f64 = 1.0;
+    let clips = [
+        (-dlat, p.0 - b.min_lat),
+        (dlat, b.max_lat - p.0),
+        (-dlng, p.1 - b.min_lng),
+        (dlng, b.max_lng - p.1),
+    ];
+    for &(d, dist) in &clips {
+        if d == 0.0 {
+            if dist < 0.0 {
+                return false;
+            }
+        } else {
+            let t = dist / d;
+            if d < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+    t0 <= t1
+}
@@ -0,0 +1,361 @@
+//! Great-circle distances and bearings, and the models of the earth used to
+//! measure distances.
+
+use crate::geodesic::Ellipsoid;
+use crate::{
+    bounding_box, bounding_box_int_with_precision, decode_center, encode_with_precision, Box,
+};
+
+/// Mean radius of the earth in meters.
+pub const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// DistanceModel is the shape of the earth used to measure distances.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistanceModel {
+    /// Sphere measures great-circle distances on a sphere with the given
+    /// radius in meters.
+    Sphere(f64),
+    /// Ellipsoid measures geodesic distances on the given ellipsoid.
+    Ellipsoid(Ellipsoid),
+}
+
+impl Default for DistanceModel {
+    fn default() -> DistanceModel {
+        DistanceModel::Sphere(EARTH_RADIUS)
+    }
+}
+
+impl DistanceModel {
+    /// distance returns the distance in meters between the points (lat1, lng1)
+    /// and (lat2, lng2).
+    pub fn distance(&self, lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+        match self {
+            DistanceModel::Sphere(radius) => {
+                distance(lat1, lng1, lat2, lng2) / EARTH_RADIUS * radius
+            }
+            DistanceModel::Ellipsoid(e) => e.distance(lat1, lng1, lat2, lng2),
+        }
+    }
+
+    /// min_radius returns the smallest radius of curvature in meters, so that
+    /// a distance d spans an angle of at most d / min_radius radians.
+    pub(crate) fn min_radius(&self) -> f64 {
+        match self {
+            DistanceModel::Sphere(radius) => *radius,
+            DistanceModel::Ellipsoid(e) => e.b() * e.b() / e.a,
+        }
+    }
+
+    /// max_distance returns an upper bound in meters on the distance between
+    /// any two points.
+    pub(crate) fn max_distance(&self) -> f64 {
+        match self {
+            DistanceModel::Sphere(radius) => std::f64::consts::PI * radius,
+            DistanceModel::Ellipsoid(e) => std::f64::consts::PI * e.a,
+        }
+    }
+
+    /// area returns the area of the box in square meters. A box with min_lng
+    /// greater than max_lng crosses the antimeridian.
+    pub fn area(&self, b: &Box) -> f64 {
+        let dlng = lng_span(b).to_radians();
+        match self {
+            DistanceModel::Sphere(radius) => {
+                let (sin1, sin2) = (b.min_lat.to_radians().sin(), b.max_lat.to_radians().sin());
+                radius * radius * dlng * (sin2 - sin1)
+            }
+            DistanceModel::Ellipsoid(e) => dlng * (e.zone_area(b.max_lat) - e.zone_area(b.min_lat)),
+        }
+    }
+
+    /// parallel_length returns the length in meters of the arc of the parallel
+    /// at lat that spans dlng degrees of longitude.
+    pub fn parallel_length(&self, lat: f64, dlng: f64) -> f64 {
+        let radius = match self {
+            DistanceModel::Sphere(radius) => radius * lat.to_radians().cos(),
+            DistanceModel::Ellipsoid(e) => e.parallel_radius(lat),
+        };
+        radius * dlng.to_radians()
+    }
+
+    /// meridian_length returns the length in meters of the arc of a meridian
+    /// between the latitudes lat1 and lat2.
+    pub fn meridian_length(&self, lat1: f64, lat2: f64) -> f64 {
+        self.distance(lat1, 0.0, lat2, 0.0)
+    }
+}
+
+/// distance returns the great-circle distance in meters between the points
+/// (lat1, lng1) and (lat2, lng2), using the haversine formula.
+pub fn distance(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let dlat = lat2 - lat1;
+    let dlng = (lng2 - lng1).to_radians();
+    let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * a.sqrt().min(1.0).asin()
+}
+
+/// initial_bearing returns the bearing in degrees clockwise from north, in the
+/// range [0, 360), at which the great circle from (lat1, lng1) to (lat2, lng2)
+/// departs.
+pub fn initial_bearing(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let dlng = (lng2 - lng1).to_radians();
+    let y = dlng.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlng.cos();
+    (y.atan2(x).to_degrees() + 360.0) % 360.0
+}
+
+/// destination returns the point (lat, lng) reached by travelling distance
+/// meters from (lat, lng) along the great circle with the given initial
+/// bearing in degrees. The longitude is normalized to [-180, 180).
+pub fn destination(lat: f64, lng: f64, bearing: f64, distance: f64) -> (f64, f64) {
+    let d = distance / EARTH_RADIUS;
+    let bearing = bearing.to_radians();
+    let lat1 = lat.to_radians();
+    let lng1 = lng.to_radians();
+    let lat2 = (lat1.sin() * d.cos() + lat1.cos() * d.sin() * bearing.cos()).asin();
+    let lng2 =
+        lng1 + (bearing.sin() * d.sin() * lat1.cos()).atan2(d.cos() - lat1.sin() * lat2.sin());
+    (lat2.to_degrees(), normalize_lng(lng2.to_degrees()))
+}
+
+/// distance_between_hashes returns the great-circle distance in meters between
+/// the centers of the cells of two string geohashes.
+pub fn distance_between_hashes(a: &str, b: &str) -> f64 {
+    let (lat1, lng1) = decode_center(a);
+    let (lat2, lng2) = decode_center(b);
+    distance(lat1, lng1, lat2, lng2)
+}
+
+/// move_hash returns the string geohash, at the same precision as hash, of the
+/// point reached by travelling meters from the center of the cell of hash along
+/// the great circle with the given initial bearing in degrees. The empty hash,
+/// whose cell is the whole world, stays empty.
+pub fn move_hash(hash: &str, bearing: f64, meters: f64) -> String {
+    if hash.is_empty() {
+        return String::new();
+    }
+    let (lat, lng) = decode_center(hash);
+    let (lat, lng) = destination(lat, lng, bearing, meters);
+    encode_with_precision(lat, lng, hash.len())
+}
+
+/// edge_points returns the points on the boundary of the box where the
+/// distance from (lat, lng) may be minimal or maximal: the corners, and the
+/// critical points of the distance along each edge.
+fn edge_points(lat: f64, lng: f64, b: &Box, model: &DistanceModel) -> Vec<(f64, f64)> {
+    let mut points = vec![
+        (b.min_lat, b.min_lng),
+        (b.min_lat, b.max_lng),
+        (b.max_lat, b.max_lng),
+        (b.max_lat, b.min_lng),
+    ];
+    for &edge_lat in &[b.min_lat, b.max_lat] {
+        // Along a parallel the distance grows with the difference in longitude.
+        for &l in &[lng, lng + 180.0] {
+            if let Some(l) = lng_within(l, b.min_lng, b.max_lng) {
+                points.push((edge_lat, l));
+            }
+        }
+    }
+    for &edge_lng in &[b.min_lng, b.max_lng] {
+        // Along a meridian the distance has a single extreme, at the latitude
+        // where the shortest path to the point crosses it at a right angle.
+        match model {
+            DistanceModel::Sphere(_) => {
+                let dlng = (edge_lng - lng).to_radians();
+                let crit = (lat.to_radians().tan() / dlng.cos()).atan().to_degrees();
+                if crit.is_finite() {
+                    points.push((crit.max(b.min_lat).min(b.max_lat), edge_lng));
+                }
+            }
+            DistanceModel::Ellipsoid(_) => {
+                let f = |edge_lat| model.distance(lat, lng, edge_lat, edge_lng);
+                for &maximize in &[false, true] {
+                    let crit = ternary_search(b.min_lat, b.max_lat, &f, maximize);
+                    points.push((crit, edge_lng));
+                }
+            }
+        }
+    }
+    points
+}
+
+/// ternary_search returns the argument in [lo, hi] that minimizes or maximizes
+/// the unimodal function f.
+fn ternary_search<F: Fn(f64) -> f64>(mut lo: f64, mut hi: f64, f: &F, maximize: bool) -> f64 {
+    for _ in 0..100 {
+        let m1 = lo + (hi - lo) / 3.0;
+        let m2 = hi - (hi - lo) / 3.0;
+        if (f(m1) < f(m2)) != maximize {
+            hi = m2;
+        } else {
+            lo = m1;
+        }
+        if hi - lo < 1e-12 {
+            break;
+        }
+    }
+    (lo + hi) / 2.0
+}
+
+/// lng_within returns the longitude equivalent to lng that lies within
+/// [min_lng, max_lng], if any.
+fn lng_within(lng: f64, min_lng: f64, max_lng: f64) -> Option<f64> {
+    [lng, lng - 360.0, lng + 360.0]
+        .iter()
+        .copied()
+        .find(|&l| min_lng <= l && l <= max_lng)
+}
+
+/// min_distance returns the smallest great-circle distance in meters from
+/// (lat, lng) to any point of the box. The closest point may lie on an edge
+/// of the box rather than a corner. A box with min_lng greater than max_lng
+/// crosses the antimeridian.
+pub fn min_distance(lat: f64, lng: f64, b: &Box) -> f64 {
+    min_distance_with_model(lat, lng, b, &DistanceModel::default())
+}
+
+/// max_distance returns the largest great-circle distance in meters from
+/// (lat, lng) to any point of the box. A box with min_lng greater than max_lng
+/// crosses the antimeridian.
+pub fn max_distance(lat: f64, lng: f64, b: &Box) -> f64 {
+    max_distance_with_model(lat, lng, b, &DistanceModel::default())
+}
+
+/// min_distance_with_model returns the smallest distance in meters, as
+/// measured by the model, from (lat, lng) to any point of the box.
+pub fn min_distance_with_model(lat: f64, lng: f64, b: &Box, model: &DistanceModel) -> f64 {
+    if let Some((west, east)) = split_antimeridian(b) {
+        let west = min_distance_with_model(lat, lng, &west, model);
+        return west.min(min_distance_with_model(lat, lng, &east, model));
+    }
+    if lat >= b.min_lat && lat <= b.max_lat && lng_within(lng, b.min_lng, b.max_lng).is_some() {
+        return 0.0;
+    }
+    edge_points(lat, lng, b, model)
+        .iter()
+        .map(|&(plat, plng)| model.distance(lat, lng, plat, plng))
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// max_distance_with_model returns the largest distance in meters, as
+/// measured by the model, from (lat, lng) to any point of the box.
+pub fn max_distance_with_model(lat: f64, lng: f64, b: &Box, model: &DistanceModel) -> f64 {
+    if let Some((west, east)) = split_antimeridian(b) {
+        let west = max_distance_with_model(lat, lng, &west, model);
+        return west.max(max_distance_with_model(lat, lng, &east, model));
+    }
+    let (alat, alng) = (-lat, lng + 180.0);
+    if alat >= b.min_lat && alat <= b.max_lat && lng_within(alng, b.min_lng, b.max_lng).is_some() {
+        return model.max_distance();
+    }
+    edge_points(lat, lng, b, model)
+        .iter()
+        .map(|&(plat, plng)| model.distance(lat, lng, plat, plng))
+        .fold(0.0, f64::max)
+}
+
+/// min_distance_to_hash returns the smallest great-circle distance in meters
+/// from (lat, lng) to any point of the cell of the string geohash.
+pub fn min_distance_to_hash(lat: f64, lng: f64, hash: &str) -> f64 {
+    min_distance(lat, lng, &bounding_box(hash))
+}
+
+/// max_distance_to_hash returns the largest great-circle distance in meters
+/// from (lat, lng) to any point of the cell of the string geohash.
+pub fn max_distance_to_hash(lat: f64, lng: f64, hash: &str) -> f64 {
+    max_distance(lat, lng, &bounding_box(hash))
+}
+
+/// min_distance_to_int_with_precision returns the smallest great-circle
+/// distance in meters from (lat, lng) to any point of the cell of the integer
+/// geohash with bits of precision.
+pub fn min_distance_to_int_with_precision(lat: f64, lng: f64, hash: u64, bits: usize) -> f64 {
+    min_distance(lat, lng, &bounding_box_int_with_precision(hash, bits))
+}
+
+/// max_distance_to_int_with_precision returns the largest great-circle
+/// distance in meters from (lat, lng) to any point of the cell of the integer
+/// geohash with bits of precision.
+pub fn max_distance_to_int_with_precision(lat: f64, lng: f64, hash: u64, bits: usize) -> f64 {
+    max_distance(lat, lng, &bounding_box_int_with_precision(hash, bits))
+}
+
+/// cell_width_m returns the east-west size in meters of the cell of the string
+/// geohash, measured along the parallel through its center.
+pub fn cell_width_m(hash: &str) -> f64 {
+    cell_width_m_with_model(hash, &DistanceModel::default())
+}
+
+/// cell_height_m returns the north-south size in meters of the cell of the
+/// string geohash.
+pub fn cell_height_m(hash: &str) -> f64 {
+    cell_height_m_with_model(hash, &DistanceModel::default())
+}
+
+/// cell_area_m2 returns the area in square meters of the cell of the string
+/// geohash.
+pub fn cell_area_m2(hash: &str) -> f64 {
+    cell_area_m2_with_model(hash, &DistanceModel::default())
+}
+
+/// cell_width_m_with_model returns the east-west size in meters, as measured
+/// by the model, of the cell of the string geohash along the parallel through
+/// its center.
+pub fn cell_width_m_with_model(hash: &str, model: &DistanceModel) -> f64 {
+    let b = bounding_box(hash);
+    let (lat, _) = b.center();
+    model.parallel_length(lat, b.max_lng - b.min_lng)
+}
+
+/// cell_height_m_with_model returns the north-south size in meters, as
+/// measured by the model, of the cell of the string geohash.
+pub fn cell_height_m_with_model(hash: &str, model: &DistanceModel) -> f64 {
+    let b = bounding_box(hash);
+    model.meridian_length(b.min_lat, b.max_lat)
+}
+
+/// cell_area_m2_with_model returns the area in square meters, as measured by
+/// the model, of the cell of the string geohash.
+pub fn cell_area_m2_with_model(hash: &str, model: &DistanceModel) -> f64 {
+    model.area(&bounding_box(hash))
+}
+
+/// lng_span returns the width of the box in degrees of longitude.
+fn lng_span(b: &Box) -> f64 {
+    if b.min_lng <= b.max_lng {
+        b.max_lng - b.min_lng
+    } else {
+        b.max_lng + 360.0 - b.min_lng
+    }
+}
+
+/// split_antimeridian splits a box that crosses the antimeridian into its
+/// western and eastern parts.
+fn split_antimeridian(b: &Box) -> Option<(Box, Box)> {
+    if b.min_lng <= b.max_lng {
+        return None;
+    }
+    Some((
+        Box {
+            max_lng: 180.0,
+            ..*b
+        },
+        Box {
+            min_lng: -180.0,
+            ..*b
+        },
+    ))
+}
+
+/// normalize_lng wraps the longitude into the range [-180, 180).
+pub(crate) fn normalize_lng(lng: f64) -> f64 {
+    let lng = (lng + 180.0) % 360.0;
+    if lng < 0.0 {
+        lng + 180.0
+    } else {
+        lng - 180.0
+    }
+}
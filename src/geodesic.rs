@@ -0,0 +1,391 @@
+//! Geodesics on an ellipsoid of revolution, solved with Vincenty's formulae.
+//! Nearly antipodal points, where Vincenty's inverse method does not
+//! converge, are solved by searching for the initial azimuth of the geodesic
+//! as in Karney's method.
+
+use std::f64::consts::PI;
+
+/// Ellipsoid is an oblate ellipsoid of revolution given by its equatorial
+/// radius a in meters and its flattening f.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ellipsoid {
+    pub a: f64,
+    pub f: f64,
+}
+
+/// The WGS84 ellipsoid, as used by GPS.
+pub const WGS84: Ellipsoid = Ellipsoid {
+    a: 6_378_137.0,
+    f: 1.0 / 298.257_223_563,
+};
+
+/// The GRS80 ellipsoid, as used by ETRS89 and NAD83.
+pub const GRS80: Ellipsoid = Ellipsoid {
+    a: 6_378_137.0,
+    f: 1.0 / 298.257_222_101,
+};
+
+/// Inverse is the solution of the inverse geodesic problem between two points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Inverse {
+    /// distance is the length of the geodesic in meters.
+    pub distance: f64,
+    /// azimuth1 is the azimuth of the geodesic at the first point, in degrees
+    /// clockwise from north in the range [0, 360).
+    pub azimuth1: f64,
+    /// azimuth2 is the azimuth of the geodesic at the second point, in the
+    /// direction of travel, in degrees clockwise from north in the range
+    /// [0, 360).
+    pub azimuth2: f64,
+}
+
+/// Direct is the solution of the direct geodesic problem from a point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Direct {
+    pub lat: f64,
+    pub lng: f64,
+    /// azimuth is the azimuth of the geodesic at the destination, in the
+    /// direction of travel, in degrees clockwise from north in the range
+    /// [0, 360).
+    pub azimuth: f64,
+}
+
+/// Arc is a geodesic followed from one latitude to another, with its
+/// difference in longitude and its azimuths in radians.
+struct Arc {
+    lng12: f64,
+    distance: f64,
+    azimuth1: f64,
+    azimuth2: f64,
+}
+
+/// Iteration limit and tolerance in radians for Vincenty's formulae.
+const MAX_ITERATIONS: usize = 200;
+const TOLERANCE: f64 = 1e-12;
+
+impl Default for Ellipsoid {
+    fn default() -> Ellipsoid {
+        WGS84
+    }
+}
+
+impl Ellipsoid {
+    /// new returns the ellipsoid with the equatorial radius a in meters and the
+    /// flattening f.
+    pub fn new(a: f64, f: f64) -> Ellipsoid {
+        Ellipsoid { a, f }
+    }
+
+    /// b returns the polar radius in meters.
+    pub fn b(&self) -> f64 {
+        self.a * (1.0 - self.f)
+    }
+
+    /// mean_radius returns the mean radius in meters.
+    pub fn mean_radius(&self) -> f64 {
+        (2.0 * self.a + self.b()) / 3.0
+    }
+
+    /// e2 returns the square of the first eccentricity.
+    pub fn e2(&self) -> f64 {
+        self.f * (2.0 - self.f)
+    }
+
+    /// parallel_radius returns the radius in meters of the parallel at lat.
+    pub fn parallel_radius(&self, lat: f64) -> f64 {
+        let (sin, cos) = lat.to_radians().sin_cos();
+        self.a * cos / (1.0 - self.e2() * sin * sin).sqrt()
+    }
+
+    /// zone_area returns the area in square meters between the equator and the
+    /// parallel at lat, per radian of longitude. It is negative south of the
+    /// equator.
+    pub fn zone_area(&self, lat: f64) -> f64 {
+        let sin = lat.to_radians().sin();
+        let e2 = self.e2();
+        let b = self.b();
+        if e2 == 0.0 {
+            return b * b * sin;
+        }
+        let e = e2.sqrt();
+        let q = sin / (1.0 - e2 * sin * sin) + ((1.0 + e * sin) / (1.0 - e * sin)).ln() / (2.0 * e);
+        b * b * q / 2.0
+    }
+
+    /// distance returns the length in meters of the geodesic between the
+    /// points (lat1, lng1) and (lat2, lng2).
+    pub fn distance(&self, lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+        self.inverse(lat1, lng1, lat2, lng2).distance
+    }
+
+    /// inverse solves the inverse geodesic problem, returning the distance and
+    /// azimuths between the points (lat1, lng1) and (lat2, lng2).
+    pub fn inverse(&self, lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> Inverse {
+        let (a, f, b) = (self.a, self.f, self.b());
+        let l = crate::distance::normalize_lng(lng2 - lng1).to_radians();
+        let u1 = ((1.0 - f) * lat1.to_radians().tan()).atan();
+        let u2 = ((1.0 - f) * lat2.to_radians().tan()).atan();
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let (sin_u2, cos_u2) = u2.sin_cos();
+
+        let mut lambda = l;
+        for _ in 0..MAX_ITERATIONS {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+            .sqrt();
+            if sin_sigma == 0.0 {
+                // Coincident points.
+                return Inverse {
+                    distance: 0.0,
+                    azimuth1: 0.0,
+                    azimuth2: 0.0,
+                };
+            }
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+            let cos_2sigma_m = if cos2_alpha != 0.0 {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha
+            } else {
+                // Equatorial line.
+                0.0
+            };
+            let c = f / 16.0 * cos2_alpha * (4.0 + f * (4.0 - 3.0 * cos2_alpha));
+            let prev = lambda;
+            lambda = l
+                + (1.0 - c)
+                    * f
+                    * sin_alpha
+                    * (sigma
+                        + c * sin_sigma
+                            * (cos_2sigma_m
+                                + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+            if lambda.abs() > PI + 1e-9 {
+                break;
+            }
+            if (lambda - prev).abs() < TOLERANCE {
+                let u_sq = cos2_alpha * (a * a - b * b) / (b * b);
+                let (big_a, big_b) = series(u_sq);
+                let delta_sigma = delta_sigma(big_b, sin_sigma, cos_sigma, cos_2sigma_m);
+                let (sin_lambda, cos_lambda) = lambda.sin_cos();
+                let azimuth1 =
+                    (cos_u2 * sin_lambda).atan2(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+                let azimuth2 =
+                    (cos_u1 * sin_lambda).atan2(-sin_u1 * cos_u2 + cos_u1 * sin_u2 * cos_lambda);
+                return Inverse {
+                    distance: b * big_a * (sigma - delta_sigma),
+                    azimuth1: normalize_azimuth(azimuth1.to_degrees()),
+                    azimuth2: normalize_azimuth(azimuth2.to_degrees()),
+                };
+            }
+        }
+        self.antipodal_inverse(lat1, lng1, lat2, lng2)
+    }
+
+    /// antipodal_inverse solves the inverse geodesic problem for nearly
+    /// antipodal points. The points are arranged so that lat1 <= 0, |lat2| <=
+    /// |lat1| and the difference in longitude is in [0, 180], where the
+    /// longitude reached at lat2 grows with the initial azimuth in [0, 180].
+    /// That azimuth is then found by bisection.
+    fn antipodal_inverse(&self, lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> Inverse {
+        let (mut lat1, mut lat2) = (lat1, lat2);
+        let mut lng12 = crate::distance::normalize_lng(lng2 - lng1);
+        let swap = lat1.abs() < lat2.abs();
+        if swap {
+            std::mem::swap(&mut lat1, &mut lat2);
+            lng12 = -lng12;
+        }
+        let lat_sign = if lat1 > 0.0 { -1.0 } else { 1.0 };
+        let (lat1, lat2) = (lat1 * lat_sign, lat2 * lat_sign);
+        let lng_sign = if lng12 < 0.0 { -1.0 } else { 1.0 };
+        let lng12 = (lng12 * lng_sign).to_radians();
+
+        let (mut lo, mut hi) = (0.0, PI);
+        for _ in 0..MAX_ITERATIONS {
+            let mid = (lo + hi) / 2.0;
+            if mid <= lo || mid >= hi {
+                break;
+            }
+            if self.arc(lat1, lat2, mid).lng12 < lng12 {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let arc = self.arc(lat1, lat2, (lo + hi) / 2.0);
+
+        // Undo the arrangement of the points.
+        let (mut azimuth1, mut azimuth2) = (arc.azimuth1 * lng_sign, arc.azimuth2 * lng_sign);
+        if lat_sign < 0.0 {
+            azimuth1 = PI - azimuth1;
+            azimuth2 = PI - azimuth2;
+        }
+        if swap {
+            (azimuth1, azimuth2) = (azimuth2 + PI, azimuth1 + PI);
+        }
+        Inverse {
+            distance: arc.distance,
+            azimuth1: normalize_azimuth(azimuth1.to_degrees()),
+            azimuth2: normalize_azimuth(azimuth2.to_degrees()),
+        }
+    }
+
+    /// arc follows the geodesic that leaves lat1 with the azimuth in radians
+    /// until it reaches lat2 heading north, or along the equator. Both
+    /// latitudes are in degrees, with lat1 <= 0 and |lat2| <= |lat1|.
+    fn arc(&self, lat1: f64, lat2: f64, azimuth: f64) -> Arc {
+        let (a, f, b) = (self.a, self.f, self.b());
+        let (sin_u1, cos_u1) = ((1.0 - f) * lat1.to_radians().tan()).atan().sin_cos();
+        let (sin_u2, cos_u2) = ((1.0 - f) * lat2.to_radians().tan()).atan().sin_cos();
+        let (sin_alpha1, cos_alpha1) = azimuth.sin_cos();
+        let sin_alpha = sin_alpha1 * cos_u1;
+        let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+        let sin_alpha2 = if cos_u2 != cos_u1 {
+            sin_alpha / cos_u2
+        } else {
+            sin_alpha1
+        };
+        let cos_alpha2 = if cos_u2 != cos_u1 || sin_u2.abs() != -sin_u1 {
+            ((cos_alpha1 * cos_u1).powi(2) + (cos_u2 - cos_u1) * (cos_u2 + cos_u1))
+                .max(0.0)
+                .sqrt()
+                / cos_u2
+        } else {
+            cos_alpha1.abs()
+        };
+
+        // Arcs and longitudes on the auxiliary sphere, from the node where the
+        // geodesic crosses the equator northward.
+        let sigma1 = sin_u1.atan2(cos_alpha1 * cos_u1);
+        let sigma2 = sin_u2.atan2(cos_alpha2 * cos_u2);
+        let sigma = (sigma2 - sigma1)
+            .sin()
+            .max(0.0)
+            .atan2((sigma2 - sigma1).cos());
+        let omega1 = (sin_alpha * sin_u1).atan2(cos_alpha1 * cos_u1);
+        let omega2 = (sin_alpha * sin_u2).atan2(cos_alpha2 * cos_u2);
+        let omega = (omega2 - omega1)
+            .sin()
+            .max(0.0)
+            .atan2((omega2 - omega1).cos());
+
+        let (sin_sigma, cos_sigma) = sigma.sin_cos();
+        let cos_2sigma_m = (sigma1 + sigma2).cos();
+        let c = f / 16.0 * cos2_alpha * (4.0 + f * (4.0 - 3.0 * cos2_alpha));
+        let lng12 = omega
+            - (1.0 - c)
+                * f
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m
+                            + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+        let u_sq = cos2_alpha * (a * a - b * b) / (b * b);
+        let (big_a, big_b) = series(u_sq);
+        let delta_sigma = delta_sigma(big_b, sin_sigma, cos_sigma, cos_2sigma_m);
+        Arc {
+            lng12,
+            distance: b * big_a * (sigma - delta_sigma),
+            azimuth1: azimuth,
+            azimuth2: sin_alpha2.atan2(cos_alpha2),
+        }
+    }
+
+    /// direct solves the direct geodesic problem, returning the point reached
+    /// by travelling distance meters from (lat, lng) along the geodesic with
+    /// the given initial azimuth in degrees. The longitude is normalized to
+    /// [-180, 180).
+    pub fn direct(&self, lat: f64, lng: f64, azimuth: f64, distance: f64) -> Direct {
+        let (a, f, b) = (self.a, self.f, self.b());
+        let (sin_alpha1, cos_alpha1) = azimuth.to_radians().sin_cos();
+        let tan_u1 = (1.0 - f) * lat.to_radians().tan();
+        let cos_u1 = 1.0 / (1.0 + tan_u1 * tan_u1).sqrt();
+        let sin_u1 = tan_u1 * cos_u1;
+        let sigma1 = tan_u1.atan2(cos_alpha1);
+        let sin_alpha = cos_u1 * sin_alpha1;
+        let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+        let u_sq = cos2_alpha * (a * a - b * b) / (b * b);
+        let (big_a, big_b) = series(u_sq);
+
+        let mut sigma = distance / (b * big_a);
+        let mut cos_2sigma_m;
+        let mut iterations = 0;
+        loop {
+            cos_2sigma_m = (2.0 * sigma1 + sigma).cos();
+            let (sin_sigma, cos_sigma) = sigma.sin_cos();
+            let prev = sigma;
+            sigma = distance / (b * big_a) + delta_sigma(big_b, sin_sigma, cos_sigma, cos_2sigma_m);
+            iterations += 1;
+            if (sigma - prev).abs() < TOLERANCE || iterations >= MAX_ITERATIONS {
+                break;
+            }
+        }
+        let (sin_sigma, cos_sigma) = sigma.sin_cos();
+        let x = sin_u1 * sin_sigma - cos_u1 * cos_sigma * cos_alpha1;
+        let lat2 = (sin_u1 * cos_sigma + cos_u1 * sin_sigma * cos_alpha1)
+            .atan2((1.0 - f) * (sin_alpha * sin_alpha + x * x).sqrt());
+        let lambda =
+            (sin_sigma * sin_alpha1).atan2(cos_u1 * cos_sigma - sin_u1 * sin_sigma * cos_alpha1);
+        let c = f / 16.0 * cos2_alpha * (4.0 + f * (4.0 - 3.0 * cos2_alpha));
+        let l = lambda
+            - (1.0 - c)
+                * f
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m
+                            + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+        let azimuth2 = sin_alpha.atan2(-x);
+        Direct {
+            lat: lat2.to_degrees(),
+            lng: crate::distance::normalize_lng(lng + l.to_degrees()),
+            azimuth: normalize_azimuth(azimuth2.to_degrees()),
+        }
+    }
+}
+
+/// distance returns the length in meters of the geodesic between the points
+/// (lat1, lng1) and (lat2, lng2) on the WGS84 ellipsoid.
+pub fn distance(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    WGS84.distance(lat1, lng1, lat2, lng2)
+}
+
+/// inverse solves the inverse geodesic problem on the WGS84 ellipsoid.
+pub fn inverse(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> Inverse {
+    WGS84.inverse(lat1, lng1, lat2, lng2)
+}
+
+/// direct solves the direct geodesic problem on the WGS84 ellipsoid.
+pub fn direct(lat: f64, lng: f64, azimuth: f64, distance: f64) -> Direct {
+    WGS84.direct(lat, lng, azimuth, distance)
+}
+
+/// series returns Vincenty's coefficients A and B for u².
+fn series(u_sq: f64) -> (f64, f64) {
+    let a = 1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+    let b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+    (a, b)
+}
+
+fn delta_sigma(b: f64, sin_sigma: f64, cos_sigma: f64, cos_2sigma_m: f64) -> f64 {
+    let c2 = cos_2sigma_m * cos_2sigma_m;
+    b * sin_sigma
+        * (cos_2sigma_m
+            + b / 4.0
+                * (cos_sigma * (-1.0 + 2.0 * c2)
+                    - b / 6.0
+                        * cos_2sigma_m
+                        * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                        * (-3.0 + 4.0 * c2)))
+}
+
+fn normalize_azimuth(azimuth: f64) -> f64 {
+    let azimuth = azimuth % 360.0;
+    if azimuth < 0.0 {
+        azimuth + 360.0
+    } else {
+        azimuth
+    }
+}
@@ -0,0 +1,168 @@
+//! Integer keys along a Hilbert curve, an alternative to the Z-order curve of
+//! geohashes with better locality where the quadrants of the Z-order curve
+//! meet.
+//!
+//! The functions mirror the integer geohash API, with the same Box type and
+//! precision semantics: a key with bits of precision is the prefix of the
+//! 64-bit key of every point in its cell, and its cell has the area of a
+//! geohash cell with the same bits. Cells with an even number of bits are the
+//! same as geohash cells. Cells with an odd number of bits are half of one,
+//! split along either axis as the curve goes.
+
+use crate::{box_curve_ranges, decode_range, encode_range, low_mask, shl, Box, Direction};
+
+/// Masks of the orientation of the curve within a cell. A swapped curve runs
+/// along the latitude first, and an inverted one runs backwards.
+pub(crate) const SWAP_MASK: usize = 1;
+pub(crate) const INVERT_MASK: usize = 2;
+/// Position along the curve of the child cell at ij, with the bit of the
+/// longitude (or S2 i) above that of the latitude (or S2 j), for each
+/// orientation.
+pub(crate) const IJ_TO_POS: [[usize; 4]; 4] =
+    [[0, 1, 3, 2], [0, 3, 1, 2], [2, 3, 1, 0], [2, 1, 3, 0]];
+/// ij of the child cell at each position along the curve, for each
+/// orientation.
+pub(crate) const POS_TO_IJ: [[usize; 4]; 4] =
+    [[0, 1, 3, 2], [0, 2, 3, 1], [3, 2, 0, 1], [3, 1, 0, 2]];
+/// Change of orientation of the curve in the child cell at each position.
+pub(crate) const POS_TO_ORIENTATION: [usize; 4] = [SWAP_MASK, 0, 0, INVERT_MASK | SWAP_MASK];
+
+/// encode_int encodes the point (lat, lng) to a 64-bit Hilbert key.
+pub fn encode_int(lat: f64, lng: f64) -> u64 {
+    let lat_int = encode_range(lat, 90.0);
+    let lng_int = encode_range(lng, 180.0);
+    let mut key = 0;
+    let mut orientation = 0;
+    for k in (0..32).rev() {
+        let ij = ((lng_int >> k) & 1) << 1 | (lat_int >> k) & 1;
+        let pos = IJ_TO_POS[orientation][ij as usize];
+        key = key << 2 | pos as u64;
+        orientation ^= POS_TO_ORIENTATION[pos];
+    }
+    key
+}
+
+/// encode_int_with_precision encodes the point (lat, lng) to a Hilbert key
+/// with the specified number of bits.
+pub fn encode_int_with_precision(lat: f64, lng: f64, bits: usize) -> u64 {
+    let hash = encode_int(lat, lng);
+    hash >> (64 - bits)
+}
+
+/// bounding_box_int_with_precision returns the region of the Hilbert key with
+/// the specified precision.
+pub fn bounding_box_int_with_precision(hash: u64, bits: usize) -> Box {
+    let ((lat0, lat1), (lng0, lng1)) = cell_int_range(hash, bits);
+    let max = |x: u32, r: f64| {
+        if x == u32::MAX {
+            r
+        } else {
+            decode_range(x + 1, r)
+        }
+    };
+    Box {
+        min_lat: decode_range(lat0, 90.0),
+        max_lat: max(lat1, 90.0),
+        min_lng: decode_range(lng0, 180.0),
+        max_lng: max(lng1, 180.0),
+    }
+}
+
+/// bounding_box_int returns the region of the 64-bit Hilbert key.
+pub fn bounding_box_int(hash: u64) -> Box {
+    bounding_box_int_with_precision(hash, 64)
+}
+
+/// decode_int_with_precision decodes the Hilbert key with bits of precision to
+/// a (lat, lng) point.
+pub fn decode_int_with_precision(hash: u64, bits: usize) -> (f64, f64) {
+    let b = bounding_box_int_with_precision(hash, bits);
+    b.round()
+}
+
+/// decode_int decodes the 64-bit Hilbert key to a (lat, lng) point.
+pub fn decode_int(hash: u64) -> (f64, f64) {
+    decode_int_with_precision(hash, 64)
+}
+
+/// neighbors_int returns the keys of the neighbors of the 64-bit Hilbert key,
+/// in the order of the directions.
+pub fn neighbors_int(hash: u64) -> [u64; 8] {
+    neighbors_int_with_precision(hash, 64)
+}
+
+/// neighbors_int_with_precision returns the keys of the neighbors of the
+/// Hilbert key at the given precision, in the order of the directions.
+pub fn neighbors_int_with_precision(hash: u64, bits: usize) -> [u64; 8] {
+    let b = bounding_box_int_with_precision(hash, bits);
+    let (lat, lng) = b.center();
+    let lat_delta = b.max_lat - b.min_lat;
+    let lng_delta = b.max_lng - b.min_lng;
+    [
+        // N
+        encode_int_with_precision(lat + lat_delta, lng, bits),
+        // NE,
+        encode_int_with_precision(lat + lat_delta, lng + lng_delta, bits),
+        // E,
+        encode_int_with_precision(lat, lng + lng_delta, bits),
+        // SE,
+        encode_int_with_precision(lat - lat_delta, lng + lng_delta, bits),
+        // S,
+        encode_int_with_precision(lat - lat_delta, lng, bits),
+        // SW,
+        encode_int_with_precision(lat - lat_delta, lng - lng_delta, bits),
+        // W,
+        encode_int_with_precision(lat, lng - lng_delta, bits),
+        // NW
+        encode_int_with_precision(lat + lat_delta, lng - lng_delta, bits),
+    ]
+}
+
+/// neighbor_int_with_precision returns the key of the neighbor of the Hilbert
+/// key in the direction at the given precision.
+pub fn neighbor_int_with_precision(hash: u64, bits: usize, direction: Direction) -> u64 {
+    neighbors_int_with_precision(hash, bits)[direction]
+}
+
+/// box_to_ranges decomposes the box into a list of inclusive [first, last]
+/// ranges of 64-bit Hilbert keys, with the same guarantees and limits as the
+/// box_to_ranges of integer geohashes.
+pub fn box_to_ranges(b: &Box, max_bits: usize, max_ranges: usize) -> Vec<(u64, u64)> {
+    box_curve_ranges(b, max_bits, max_ranges, cell_int_range)
+}
+
+/// cell_int_range returns the inclusive ranges of the 32-bit latitude and
+/// longitude integers covered by the Hilbert key with bits of precision.
+fn cell_int_range(hash: u64, bits: usize) -> ((u32, u32), (u32, u32)) {
+    let full = shl(hash, 64 - bits);
+    let levels = bits / 2;
+    let (mut lat, mut lng) = (0u64, 0u64);
+    let mut orientation = 0;
+    for k in 0..levels {
+        let pos = ((full >> (62 - 2 * k)) & 3) as usize;
+        let ij = POS_TO_IJ[orientation][pos];
+        lng = lng << 1 | (ij >> 1) as u64;
+        lat = lat << 1 | (ij & 1) as u64;
+        orientation ^= POS_TO_ORIENTATION[pos];
+    }
+    let range = |x: u64, level: usize| {
+        let lo = shl(x, 32 - level) as u32;
+        (lo, lo | low_mask(32 - level))
+    };
+    if bits & 1 == 0 {
+        return (range(lat, levels), range(lng, levels));
+    }
+    // The first or last two children of the cell, which share a side.
+    let half = ((full >> (62 - 2 * levels)) & 2) as usize;
+    let (a, b) = (
+        POS_TO_IJ[orientation][half],
+        POS_TO_IJ[orientation][half + 1],
+    );
+    let child = |x: u64, bit: usize| range(x << 1 | bit as u64, levels + 1);
+    let (lat_a, lat_b) = (child(lat, a & 1), child(lat, b & 1));
+    let (lng_a, lng_b) = (child(lng, a >> 1), child(lng, b >> 1));
+    (
+        (lat_a.0.min(lat_b.0), lat_a.1.max(lat_b.1)),
+        (lng_a.0.min(lng_b.0), lng_a.1.max(lng_b.1)),
+    )
+}
@@ -1,7 +1,30 @@
-pub mod base32;
+//! Crate geohash provides encoding and decoding of string and integer
+//! geohashes.
 
-/// Crate geohash provides encoding and decoding of string and integer
-/// geohashes.
+pub mod alphabet;
+pub mod altitude;
+pub mod base32;
+pub mod cellunion;
+pub mod components;
+pub mod cover;
+pub mod distance;
+pub mod geodesic;
+pub mod hilbert;
+pub mod maidenhead;
+pub mod mgrs;
+pub mod morton;
+pub mod olc;
+pub mod outline;
+pub mod point;
+pub mod s2;
+pub mod space;
+pub mod spacetime;
+pub mod tile;
+pub mod topology;
+pub mod utm;
+
+use alphabet::Codec;
+use space::GeohashSpace;
 
 /// Direction represents directions in the latitute/longitude space.
 pub type Direction = usize;
@@ -25,28 +48,22 @@ pub fn encode(lat: f64, lng: f64) -> String {
 /// encode_with_precision encodes the point (lat, lng) as a string geohash with
 /// the specified number of characters of precision (max 12).
 pub fn encode_with_precision(lat: f64, lng: f64, chars: usize) -> String {
-    let bits = 5 * chars;
-    let inthash = encode_int_with_precision(lat, lng, bits);
-    let enc = base32::encode(inthash);
-    std::str::from_utf8(&enc[12 - chars..]).unwrap().to_owned()
+    GeohashSpace::default().encode_with_precision(lng, lat, chars)
 }
 
 /// encode_int encodes the point (lat, lng) to a 64-bit integer geohash.
 pub fn encode_int(lat: f64, lng: f64) -> u64 {
-    let lat_int = encode_range(lat, 90.0);
-    let lng_int = encode_range(lng, 180.0);
-    interleave(lat_int, lng_int)
+    GeohashSpace::default().encode_int(lng, lat)
 }
 
 /// encode_int_with_precision encodes the point (lat, lng) to an integer with the
 /// specified number of bits.
 pub fn encode_int_with_precision(lat: f64, lng: f64, bits: usize) -> u64 {
-    let hash = encode_int(lat, lng);
-    hash >> (64 - bits)
+    GeohashSpace::default().encode_int_with_precision(lng, lat, bits)
 }
 
 /// Box represents a rectangle in latitude/longitude space.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Box {
     pub min_lat: f64,
     pub max_lat: f64,
@@ -68,13 +85,43 @@ impl Box {
         self.min_lat <= lat && lat <= self.max_lat && self.min_lng <= lng && lng <= self.max_lng
     }
 
+    /// contains_box decides whether the other box lies completely within the
+    /// box. The containment test is inclusive of the edges and corners.
+    pub fn contains_box(&self, other: &Box) -> bool {
+        self.min_lat <= other.min_lat
+            && other.max_lat <= self.max_lat
+            && self.min_lng <= other.min_lng
+            && other.max_lng <= self.max_lng
+    }
+
+    /// intersects decides whether the box and the other box have any point in
+    /// common, including points on the edges and corners.
+    pub fn intersects(&self, other: &Box) -> bool {
+        self.min_lat <= other.max_lat
+            && other.min_lat <= self.max_lat
+            && self.min_lng <= other.max_lng
+            && other.min_lng <= self.max_lng
+    }
+
+    /// area_m2 returns the area of the box in square meters on the default
+    /// spherical earth.
+    pub fn area_m2(&self) -> f64 {
+        self.area_m2_with_model(&distance::DistanceModel::default())
+    }
+
+    /// area_m2_with_model returns the area of the box in square meters, as
+    /// measured by the model.
+    pub fn area_m2_with_model(&self, model: &distance::DistanceModel) -> f64 {
+        model.area(self)
+    }
+
     /// round returns a point inside the box, making an effort to round to minimal
     /// precision.
     pub fn round(&self) -> (f64, f64) {
         let x = max_decimal_power(self.max_lat - self.min_lat);
-        let lat = (self.min_lat / x).ceil() * x;
+        let lat = ((self.min_lat / x).ceil() * x).max(self.min_lat);
         let x = max_decimal_power(self.max_lng - self.min_lng);
-        let lng = (self.min_lng / x).ceil() * x;
+        let lng = ((self.min_lng / x).ceil() * x).max(self.min_lng);
         (lat, lng)
     }
 }
@@ -104,26 +151,13 @@ pub fn error_with_precision(bits: usize) -> (f64, f64) {
 
 /// bounding_box returns the region encoded by the given string geohash.
 pub fn bounding_box(hash: &str) -> Box {
-    let bits = 5 * hash.len();
-    let inthash = base32::decode(hash.as_bytes());
-    bounding_box_int_with_precision(inthash, bits)
+    GeohashSpace::default().bounding_box(hash)
 }
 
 /// bounding_box_int_with_precision returns the region encoded by the integer
 /// geohash with the specified precision.
 pub fn bounding_box_int_with_precision(hash: u64, bits: usize) -> Box {
-    let full_hash = hash << (64 - bits);
-    let (lat_int, lng_int) = deinterleave(full_hash);
-    let lat = decode_range(lat_int, 90.0);
-    let lng = decode_range(lng_int, 180.0);
-    let (lat_err, lng_err) = error_with_precision(bits);
-    let b = Box {
-        min_lat: lat,
-        max_lat: lat + lat_err,
-        min_lng: lng,
-        max_lng: lng + lng_err,
-    };
-    b
+    GeohashSpace::default().bounding_box_int_with_precision(hash, bits)
 }
 
 /// bounding_box_int returns the region encoded by the given 64-bit integer
@@ -148,23 +182,44 @@ pub fn validate(hash: &str) -> Result<bool, String> {
     Ok(true)
 }
 
+/// encode_with_codec encodes the point (lat, lng) to a string with the
+/// specified number of characters of the codec, such as alphabet::BASE16.
+pub fn encode_with_codec<C: Codec + ?Sized>(lat: f64, lng: f64, chars: usize, codec: &C) -> String {
+    codec.encode(lat, lng, chars)
+}
+
+/// bounding_box_with_codec returns the region of the string of the codec.
+pub fn bounding_box_with_codec<C: Codec + ?Sized>(hash: &str, codec: &C) -> Result<Box, String> {
+    codec.bounding_box(hash)
+}
+
+/// decode_with_codec decodes the string of the codec to a (lat, lng) point.
+pub fn decode_with_codec<C: Codec + ?Sized>(hash: &str, codec: &C) -> Result<(f64, f64), String> {
+    Ok(codec.bounding_box(hash)?.round())
+}
+
+/// validate_with_codec validates the string of the codec.
+pub fn validate_with_codec<C: Codec + ?Sized>(hash: &str, codec: &C) -> Result<bool, String> {
+    codec.validate(hash)
+}
+
 /// decode the string geohash to a (lat, lng) point.
 pub fn decode(hash: &str) -> (f64, f64) {
-    let b = bounding_box(hash);
-    b.round()
+    let (lng, lat) = GeohashSpace::default().decode(hash);
+    (lat, lng)
 }
 
 /// decode_center decodes the string geohash to the central point (lat, lng) of the bounding box.
 pub fn decode_center(hash: &str) -> (f64, f64) {
-    let b = bounding_box(hash);
-    b.center()
+    let (lng, lat) = GeohashSpace::default().decode_center(hash);
+    (lat, lng)
 }
 
 /// decode_int_with_precision decodes the provided integer geohash with bits of
 /// precision to a (lat, lng) point.
 pub fn decode_int_with_precision(hash: u64, bits: usize) -> (f64, f64) {
-    let b = bounding_box_int_with_precision(hash, bits);
-    return b.round();
+    let (lng, lat) = GeohashSpace::default().decode_int_with_precision(hash, bits);
+    (lat, lng)
 }
 
 /// decode_int_with_precision decodes the provided 64-bit integer geohash to a (lat, lng) point.
@@ -175,29 +230,7 @@ pub fn decode_int(hash: u64) -> (f64, f64) {
 /// neighbors returns a slice of geohash strings that correspond to the provided
 /// geohash's neighbors.
 pub fn neighbors(hash: &str) -> [String; 8] {
-    let b = bounding_box(hash);
-    let (lat, lng) = b.center();
-    let lat_delta = b.max_lat - b.min_lat;
-    let lng_delta = b.max_lng - b.min_lng;
-    let precision = hash.len();
-    [
-        // N
-        encode_with_precision(lat + lat_delta, lng, precision),
-        // NE,
-        encode_with_precision(lat + lat_delta, lng + lng_delta, precision),
-        // E,
-        encode_with_precision(lat, lng + lng_delta, precision),
-        // SE,
-        encode_with_precision(lat - lat_delta, lng + lng_delta, precision),
-        // S,
-        encode_with_precision(lat - lat_delta, lng, precision),
-        // SW,
-        encode_with_precision(lat - lat_delta, lng - lng_delta, precision),
-        // W,
-        encode_with_precision(lat, lng - lng_delta, precision),
-        // NW
-        encode_with_precision(lat + lat_delta, lng - lng_delta, precision),
-    ]
+    GeohashSpace::default().neighbors(hash)
 }
 
 /// neighbors_int returns a slice of uint64s that correspond to the provided hash's
@@ -209,28 +242,7 @@ pub fn neighbors_int(hash: u64) -> [u64; 8] {
 /// neighbors_int_with_precision returns a slice of uint64s that correspond to the
 /// provided hash's neighbors at the given precision.
 pub fn neighbors_int_with_precision(hash: u64, bits: usize) -> [u64; 8] {
-    let b = bounding_box_int_with_precision(hash, bits);
-    let (lat, lng) = b.center();
-    let lat_delta = b.max_lat - b.min_lat;
-    let lng_delta = b.max_lng - b.min_lng;
-    [
-        // N
-        encode_int_with_precision(lat + lat_delta, lng, bits),
-        // NE,
-        encode_int_with_precision(lat + lat_delta, lng + lng_delta, bits),
-        // E,
-        encode_int_with_precision(lat, lng + lng_delta, bits),
-        // SE,
-        encode_int_with_precision(lat - lat_delta, lng + lng_delta, bits),
-        // S,
-        encode_int_with_precision(lat - lat_delta, lng, bits),
-        // SW,
-        encode_int_with_precision(lat - lat_delta, lng - lng_delta, bits),
-        // W,
-        encode_int_with_precision(lat, lng - lng_delta, bits),
-        // NW
-        encode_int_with_precision(lat + lat_delta, lng - lng_delta, bits),
-    ]
+    GeohashSpace::default().neighbors_int_with_precision(hash, bits)
 }
 
 /// neighbor returns a geohash string that corresponds to the provided
@@ -251,57 +263,567 @@ pub fn neighbor_int_with_precision(hash: u64, bits: usize, direction: Direction)
     neighbors_int_with_precision(hash, bits)[direction]
 }
 
+/// prefix_range returns the inclusive range [min, max] of 64-bit integer
+/// geohashes, as produced by encode_int, that lie in the cell of the given
+/// string geohash.
+pub fn prefix_range(hash: &str) -> (u64, u64) {
+    let bits = 5 * hash.len();
+    let inthash = base32::decode(hash.as_bytes());
+    int_prefix_range(inthash, bits, 64)
+}
+
+/// int_prefix_range returns the inclusive range [min, max] of integer geohashes
+/// with target_bits of precision that lie in the cell of the integer geohash
+/// with bits of precision. When target_bits is less than bits, the range holds
+/// only the ancestor of the hash at target_bits.
+pub fn int_prefix_range(hash: u64, bits: usize, target_bits: usize) -> (u64, u64) {
+    if target_bits <= bits {
+        let hash = shr(hash, bits - target_bits);
+        return (hash, hash);
+    }
+    let shift = target_bits - bits;
+    let min = shl(hash, shift);
+    (min, min | low_mask64(shift))
+}
+
+/// range_prefix returns the string geohash whose cell holds exactly the 64-bit
+/// integer geohashes in the inclusive range [min, max], or None if the range is
+/// not aligned to a string geohash cell.
+pub fn range_prefix(min: u64, max: u64) -> Option<String> {
+    let (hash, bits) = int_range_prefix(min, max, 64)?;
+    if bits % 5 != 0 || bits > 60 {
+        return None;
+    }
+    Some(base32_string(hash, bits / 5))
+}
+
+/// int_range_prefix returns the integer geohash and its precision (hash, bits)
+/// whose cell holds exactly the integer geohashes with target_bits of precision
+/// in the inclusive range [min, max], or None if the range is not aligned to a
+/// cell.
+pub fn int_range_prefix(min: u64, max: u64, target_bits: usize) -> Option<(u64, usize)> {
+    if min > max || max > low_mask64(target_bits) {
+        return None;
+    }
+    let size = (max - min) as u128 + 1;
+    if !size.is_power_of_two() {
+        return None;
+    }
+    let shift = size.trailing_zeros() as usize;
+    if min & low_mask64(shift) != 0 {
+        return None;
+    }
+    Some((shr(min, shift), target_bits - shift))
+}
+
+/// Descendant picks which of the cells within a cell extend_bits returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Descendant {
+    /// Min is the first cell within the cell, with every added bit clear.
+    Min,
+    /// Max is the last cell within the cell, with every added bit set.
+    Max,
+}
+
+/// LeftoverBits is what int_to_string does with the bits of an integer
+/// geohash that do not fill a whole character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeftoverBits {
+    /// Error fails unless the bits fill whole characters.
+    Error,
+    /// Truncate drops the leftover bits, giving the string of the ancestor
+    /// cell that holds the cell of the hash.
+    Truncate,
+    /// Cover pads the leftover bits to a whole character every way it can,
+    /// giving the strings of the cells that together make up exactly the cell
+    /// of the hash.
+    Cover,
+}
+
+/// truncate_bits returns the ancestor with target_bits of precision of the
+/// integer geohash with bits of precision. When target_bits is not less than
+/// bits, the hash is returned unchanged.
+pub fn truncate_bits(hash: u64, bits: usize, target_bits: usize) -> u64 {
+    shr(hash, bits.saturating_sub(target_bits))
+}
+
+/// extend_bits returns the first or last descendant with target_bits of
+/// precision of the integer geohash with bits of precision. When target_bits
+/// is less than bits, the ancestor at target_bits is returned instead, as with
+/// int_prefix_range.
+pub fn extend_bits(hash: u64, bits: usize, target_bits: usize, descendant: Descendant) -> u64 {
+    let (min, max) = int_prefix_range(hash, bits, target_bits);
+    match descendant {
+        Descendant::Min => min,
+        Descendant::Max => max,
+    }
+}
+
+/// int_to_string returns the string geohashes of the integer geohash with bits
+/// of precision. Strings hold 5 bits per character, so when bits is not a
+/// multiple of 5 the leftover bits are handled as the policy says. Only Cover
+/// may return more than one string.
+pub fn int_to_string(
+    hash: u64,
+    bits: usize,
+    leftover: LeftoverBits,
+) -> Result<Vec<String>, String> {
+    if bits > 64 {
+        return Err("too many bits".to_owned());
+    }
+    let extra = bits % 5;
+    if extra == 0 {
+        return Ok(vec![base32_string(hash, bits / 5)]);
+    }
+    match leftover {
+        LeftoverBits::Error => Err(format!("{} leftover bits", extra)),
+        LeftoverBits::Truncate => Ok(vec![base32_string(shr(hash, extra), bits / 5)]),
+        LeftoverBits::Cover => {
+            let chars = bits / 5 + 1;
+            if chars > 12 {
+                return Err("too long".to_owned());
+            }
+            let (min, max) = int_prefix_range(hash, bits, 5 * chars);
+            Ok((min..=max).map(|h| base32_string(h, chars)).collect())
+        }
+    }
+}
+
+/// string_to_int returns the integer geohash with target_bits of precision of
+/// the string geohash. When target_bits is more than the bits of the string,
+/// the first descendant of its cell is returned, and when it is less, the
+/// ancestor.
+pub fn string_to_int(hash: &str, target_bits: usize) -> Result<u64, String> {
+    validate(hash)?;
+    if target_bits > 64 {
+        return Err("too many bits".to_owned());
+    }
+    let inthash = base32::decode(hash.as_bytes());
+    Ok(extend_bits(
+        inthash,
+        5 * hash.len(),
+        target_bits,
+        Descendant::Min,
+    ))
+}
+
+/// base32_string returns the string geohash with chars characters of the
+/// integer geohash with 5 * chars bits of precision.
+pub(crate) fn base32_string(hash: u64, chars: usize) -> String {
+    let enc = base32::encode(hash);
+    std::str::from_utf8(&enc[12 - chars..]).unwrap().to_owned()
+}
+
+/// box_int_corners returns the 64-bit integer geohashes of the south-west and
+/// north-east corners of the box. These span the Z-order range of the box, as
+/// used by bigmin and litmax.
+pub fn box_int_corners(b: &Box) -> (u64, u64) {
+    (
+        encode_int(b.min_lat, b.min_lng),
+        encode_int(b.max_lat, b.max_lng),
+    )
+}
+
+/// bigmin returns the smallest 64-bit integer geohash not less than hash that
+/// lies inside the box with the south-west corner min and the north-east corner
+/// max, or None if there is no such hash. A scan over the range of the box can
+/// use it to skip the keys between hash and the returned key, none of which are
+/// inside the box.
+pub fn bigmin(hash: u64, min: u64, max: u64) -> Option<u64> {
+    if z_contains(hash, min, max) {
+        return Some(hash);
+    }
+    let (mut min, mut max) = (min, max);
+    let mut result = None;
+    for i in (0..64).rev() {
+        let bit = 1u64 << i;
+        match (hash & bit != 0, min & bit != 0, max & bit != 0) {
+            (false, false, true) => {
+                result = Some(load_min(min, i));
+                max = load_max(max, i);
+            }
+            (false, true, true) => return Some(min),
+            (true, false, false) => return result,
+            (true, false, true) => min = load_min(min, i),
+            (false, true, false) | (true, true, false) => return None,
+            _ => {}
+        }
+    }
+    result
+}
+
+/// litmax returns the largest 64-bit integer geohash not greater than hash that
+/// lies inside the box with the south-west corner min and the north-east corner
+/// max, or None if there is no such hash. It is the counterpart of bigmin for
+/// scanning in descending order.
+pub fn litmax(hash: u64, min: u64, max: u64) -> Option<u64> {
+    if z_contains(hash, min, max) {
+        return Some(hash);
+    }
+    let (mut min, mut max) = (min, max);
+    let mut result = None;
+    for i in (0..64).rev() {
+        let bit = 1u64 << i;
+        match (hash & bit != 0, min & bit != 0, max & bit != 0) {
+            (false, false, true) => max = load_max(max, i),
+            (false, true, true) => return result,
+            (true, false, false) => return Some(max),
+            (true, false, true) => {
+                result = Some(load_max(max, i));
+                min = load_min(min, i);
+            }
+            (false, true, false) | (true, true, false) => return None,
+            _ => {}
+        }
+    }
+    result
+}
+
+/// z_contains decides whether the integer geohash lies in the box spanned by
+/// the integer geohashes min and max.
+fn z_contains(hash: u64, min: u64, max: u64) -> bool {
+    let (lat, lng) = deinterleave(hash);
+    let (min_lat, min_lng) = deinterleave(min);
+    let (max_lat, max_lng) = deinterleave(max);
+    min_lat <= lat && lat <= max_lat && min_lng <= lng && lng <= max_lng
+}
+
+/// dimension_mask returns the bits below bit i that belong to the same
+/// dimension as bit i.
+fn dimension_mask(i: usize) -> u64 {
+    let dim = if i & 1 == 0 {
+        0x5555555555555555
+    } else {
+        0xaaaaaaaaaaaaaaaa
+    };
+    dim & low_mask64(i)
+}
+
+/// load_min sets bit i of x and clears the lower bits of the same dimension,
+/// giving the smallest value of the upper half of the dimension's range.
+fn load_min(x: u64, i: usize) -> u64 {
+    (x | (1 << i)) & !dimension_mask(i)
+}
+
+/// load_max clears bit i of x and sets the lower bits of the same dimension,
+/// giving the largest value of the lower half of the dimension's range.
+fn load_max(x: u64, i: usize) -> u64 {
+    (x & !(1 << i)) | dimension_mask(i)
+}
+
+/// shl shifts x left by n bits, yielding zero when every bit is shifted out.
+fn shl(x: u64, n: usize) -> u64 {
+    if n >= 64 {
+        0
+    } else {
+        x << n
+    }
+}
+
+/// shr shifts x right by n bits, yielding zero when every bit is shifted out.
+fn shr(x: u64, n: usize) -> u64 {
+    if n >= 64 {
+        0
+    } else {
+        x >> n
+    }
+}
+
+/// low_mask64 returns a 64-bit word with the lowest n bits set.
+fn low_mask64(n: usize) -> u64 {
+    if n >= 64 {
+        u64::MAX
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// box_to_ranges decomposes the box into a list of inclusive [first, last]
+/// ranges of 64-bit integer geohashes, as produced by encode_int. Every point
+/// inside the box encodes to a key in one of the ranges, but the ranges may
+/// also cover keys outside of the box. The Z-order quadtree is refined no
+/// deeper than max_bits of precision, and no further once that would yield
+/// more than max_ranges ranges. Raising either improves accuracy at the cost
+/// of more or smaller ranges. The ranges are sorted and disjoint. A box with
+/// min_lng greater than max_lng crosses the antimeridian.
+///
+/// The ranges are inclusive rather than half-open [start, end), because the
+/// end of a range that holds the last key, u64::MAX, does not fit in a u64.
+pub fn box_to_ranges(b: &Box, max_bits: usize, max_ranges: usize) -> Vec<(u64, u64)> {
+    box_curve_ranges(b, max_bits, max_ranges, cell_int_range)
+}
+
+/// CellRange returns the inclusive ranges of the 32-bit latitude and longitude
+/// integers covered by a key of a curve with bits of precision.
+type CellRange = fn(u64, usize) -> ((u32, u32), (u32, u32));
+
+/// box_curve_ranges is box_to_ranges for the curve of the cell_range.
+fn box_curve_ranges(
+    b: &Box,
+    max_bits: usize,
+    max_ranges: usize,
+    cell_range: CellRange,
+) -> Vec<(u64, u64)> {
+    if b.min_lat > b.max_lat {
+        return Vec::new();
+    }
+    let lat = (encode_range(b.min_lat, 90.0), encode_range(b.max_lat, 90.0));
+    let queries: Vec<[(u32, u32); 2]> = lng_spans(b)
+        .into_iter()
+        .map(|(min_lng, max_lng)| {
+            [
+                lat,
+                (encode_range(min_lng, 180.0), encode_range(max_lng, 180.0)),
+            ]
+        })
+        .collect();
+    let cell_ranges = |key: u128, bits| {
+        let (lat, lng) = cell_range(key as u64, bits);
+        [lat, lng]
+    };
+    curve_ranges(&queries, 64, max_bits, max_ranges, cell_ranges)
+        .into_iter()
+        .map(|(first, last)| (first as u64, last as u64))
+        .collect()
+}
+
+/// lng_spans returns the spans of longitude of the box, split in two at the
+/// antimeridian when min_lng is greater than max_lng.
+fn lng_spans(b: &Box) -> Vec<(f64, f64)> {
+    if b.min_lng <= b.max_lng {
+        vec![(b.min_lng, b.max_lng)]
+    } else {
+        vec![(b.min_lng, 180.0), (-180.0, b.max_lng)]
+    }
+}
+
+/// curve_ranges decomposes the union of the queries, each made of inclusive
+/// ranges of the 32-bit integers of every axis, into sorted and disjoint
+/// inclusive [first, last] ranges of the full keys of a curve with key_bits
+/// bits (max 96). The cell_ranges returns the ranges of each axis covered by a
+/// key with bits of precision. The curve is refined no deeper than max_bits of
+/// precision, and no further once that would yield more than max_ranges
+/// ranges.
+fn curve_ranges<const N: usize, F>(
+    queries: &[[(u32, u32); N]],
+    key_bits: usize,
+    max_bits: usize,
+    max_ranges: usize,
+    cell_ranges: F,
+) -> Vec<(u128, u128)>
+where
+    F: Fn(u128, usize) -> [(u32, u32); N],
+{
+    let max_bits = max_bits.min(key_bits);
+    let max_ranges = max_ranges.max(1);
+    let key_range = |key: u128, bits: usize| {
+        let shift = key_bits - bits;
+        (key << shift, (key + 1) << shift)
+    };
+
+    // Walk the cells one bit at a time. Cells completely inside a query are
+    // final, cells straddling the edge of the queries are refined further.
+    let mut inside: Vec<(u128, u128)> = Vec::new();
+    let mut partial: Vec<u128> = vec![0];
+    let mut bits = 0;
+    while bits < max_bits && !partial.is_empty() {
+        let mut next_inside = inside.clone();
+        let mut next_partial = Vec::new();
+        for &key in &partial {
+            for &child in [key << 1, key << 1 | 1].iter() {
+                let cell = cell_ranges(child, bits + 1);
+                let overlaps = |q: &[(u32, u32); N]| (0..N).all(|i| ranges_overlap(cell[i], q[i]));
+                if !queries.iter().any(overlaps) {
+                    continue;
+                }
+                let contains = |q: &[(u32, u32); N]| (0..N).all(|i| range_contains(q[i], cell[i]));
+                if queries.iter().any(contains) {
+                    next_inside.push(key_range(child, bits + 1));
+                } else {
+                    next_partial.push(child);
+                }
+            }
+        }
+        let mut ranges = next_inside.clone();
+        ranges.extend(next_partial.iter().map(|&key| key_range(key, bits + 1)));
+        if merge_ranges(ranges, usize::MAX).len() > max_ranges && bits > 0 {
+            break;
+        }
+        inside = next_inside;
+        partial = next_partial;
+        bits += 1;
+    }
+
+    let mut ranges = inside;
+    ranges.extend(partial.iter().map(|&key| key_range(key, bits)));
+    merge_ranges(ranges, max_ranges)
+        .into_iter()
+        .map(|(start, end)| (start, end - 1))
+        .collect()
+}
+
+/// box_cells returns the integer geohashes with bits of precision whose cells
+/// overlap the inside of the box, in row-major order from the south-west.
+fn box_cells(b: &Box, bits: usize) -> Vec<u64> {
+    let lat_bits = bits / 2;
+    let lng_bits = bits - lat_bits;
+    let (lat0, lat1) = cell_indexes(b.min_lat, b.max_lat, 90.0, lat_bits);
+    let (lng0, lng1) = cell_indexes(b.min_lng, b.max_lng, 180.0, lng_bits);
+    let mut hashes = Vec::new();
+    for lat in lat0..=lat1 {
+        for lng in lng0..=lng1 {
+            let full = interleave(
+                shl(lat, 32 - lat_bits) as u32,
+                shl(lng, 32 - lng_bits) as u32,
+            );
+            hashes.push(shr(full, 64 - bits));
+        }
+    }
+    hashes
+}
+
+/// enclosing_cell returns the integer geohash with the most bits of precision
+/// (max 64) whose cell contains the whole box, as (hash, bits). Boxes that
+/// cross the antimeridian are only contained by the whole world.
+fn enclosing_cell(b: &Box) -> (u64, usize) {
+    if b.min_lng > b.max_lng {
+        return (0, 0);
+    }
+    let (lat0, lat1) = cell_indexes(b.min_lat, b.max_lat, 90.0, 32);
+    let (lng0, lng1) = cell_indexes(b.min_lng, b.max_lng, 180.0, 32);
+    let first = interleave(lat0 as u32, lng0 as u32);
+    let last = interleave(lat1 as u32, lng1 as u32);
+    let bits = (first ^ last).leading_zeros() as usize;
+    (shr(first, 64 - bits), bits)
+}
+
+/// cell_indexes returns the inclusive range of the indexes of the cells that
+/// overlap the inside of the span from min to max, when the range -r to +r is
+/// split into 2^bits cells.
+fn cell_indexes(min: f64, max: f64, r: f64, bits: usize) -> (u64, u64) {
+    let n = (bits as f64).exp2();
+    let first = ((min + r) / (2.0 * r) * n).floor().clamp(0.0, n - 1.0);
+    let last = (((max + r) / (2.0 * r) * n).ceil() - 1.0).clamp(first, n - 1.0);
+    (first as u64, last as u64)
+}
+
+/// cell_int_range returns the inclusive ranges of the 32-bit latitude and
+/// longitude integers covered by the integer geohash with bits of precision.
+fn cell_int_range(hash: u64, bits: usize) -> ((u32, u32), (u32, u32)) {
+    let full_hash = if bits == 0 { 0 } else { hash << (64 - bits) };
+    let (lat_int, lng_int) = deinterleave(full_hash);
+    let lat_bits = bits / 2;
+    let lng_bits = bits - lat_bits;
+    (
+        (lat_int, lat_int | low_mask(32 - lat_bits)),
+        (lng_int, lng_int | low_mask(32 - lng_bits)),
+    )
+}
+
+/// low_mask returns a 32-bit word with the lowest n bits set.
+fn low_mask(n: usize) -> u32 {
+    if n >= 32 {
+        u32::MAX
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// cell_key_range returns the [start, end) range of 64-bit integer geohashes
+/// covered by the integer geohash with bits of precision.
+fn cell_key_range(hash: u64, bits: usize) -> (u128, u128) {
+    let shift = 64 - bits;
+    ((hash as u128) << shift, (hash as u128 + 1) << shift)
+}
+
+fn ranges_overlap(a: (u32, u32), b: (u32, u32)) -> bool {
+    a.0 <= b.1 && b.0 <= a.1
+}
+
+fn range_contains(outer: (u32, u32), inner: (u32, u32)) -> bool {
+    outer.0 <= inner.0 && inner.1 <= outer.1
+}
+
+/// merge_ranges sorts the ranges and joins those that touch or overlap. While
+/// there are more than max_ranges left, the ranges separated by the smallest
+/// gaps are joined as well.
+fn merge_ranges(mut ranges: Vec<(u128, u128)>, max_ranges: usize) -> Vec<(u128, u128)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(u128, u128)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    if merged.len() <= max_ranges {
+        return merged;
+    }
+    let mut gaps: Vec<(u128, usize)> = merged
+        .windows(2)
+        .enumerate()
+        .map(|(i, w)| (w[1].0 - w[0].1, i))
+        .collect();
+    gaps.sort_unstable();
+    let mut join = vec![false; merged.len()];
+    for &(_, i) in gaps.iter().take(merged.len() - max_ranges) {
+        join[i] = true;
+    }
+    let mut result: Vec<(u128, u128)> = Vec::with_capacity(max_ranges);
+    let mut joining = false;
+    for (i, range) in merged.into_iter().enumerate() {
+        match result.last_mut() {
+            Some(last) if joining => last.1 = range.1,
+            _ => result.push(range),
+        }
+        joining = join[i];
+    }
+    result
+}
+
 /// precalculated for performance
 const EXP_232: f64 = 4.294967296e+09; // math.Exp2(32)
 
 /// encode_range the position of x within the range -r to +r as a 32-bit integer.
 fn encode_range(x: f64, r: f64) -> u32 {
-    let p = (x + r) / (2.0 * r);
-    (p * EXP_232) as u32
+    encode_extent(x, -r, r)
 }
 
 /// decode_range the 32-bit range encoding X back to a value in the range -r to +r.
 fn decode_range(x: u32, r: f64) -> f64 {
+    decode_extent(x, -r, r)
+}
+
+/// encode_extent returns the position of v within the range from min to max
+/// as a 32-bit integer.
+fn encode_extent(v: f64, min: f64, max: f64) -> u32 {
+    let p = (v - min) / (max - min);
+    (p * EXP_232) as u32
+}
+
+/// decode_extent returns the value at the position of the 32-bit integer
+/// within the range from min to max.
+fn decode_extent(x: u32, min: f64, max: f64) -> f64 {
     let p = x as f64 / EXP_232;
-    let x = 2.0 * r * p - r;
-    x
+    (max - min) * p + min
 }
 
-/// spread out the 32 bits of x into 64 bits, where the bits of x occupy even
-/// bit positions.
-fn spread(x: u32) -> u64 {
-    let mut x = x as u64;
-    x = (x | (x << 16)) & 0x0000ffff0000ffff;
-    x = (x | (x << 8)) & 0x00ff00ff00ff00ff;
-    x = (x | (x << 4)) & 0x0f0f0f0f0f0f0f0f;
-    x = (x | (x << 2)) & 0x3333333333333333;
-    x = (x | (x << 1)) & 0x5555555555555555;
-    x
+/// axis_bits3 returns the number of bits of the first, second and third axis
+/// of a key that interleaves three axes, with bits of precision.
+fn axis_bits3(bits: usize) -> (usize, usize, usize) {
+    (bits.div_ceil(3), (bits + 1) / 3, bits / 3)
 }
 
 /// interleave the bits of x and y. In the result, x and y occupy even and odd
 /// bitlevels, respectively.
 fn interleave(x: u32, y: u32) -> u64 {
-    spread(x) | (spread(y) << 1)
-}
-
-/// squash the even bitlevels of X into a 32-bit word. Odd bitlevels of X are
-/// ignored, and may take any value.
-fn squash(x: u64) -> u32 {
-    let mut x = x;
-    x &= 0x5555555555555555;
-    x = (x | (x >> 1)) & 0x3333333333333333;
-    x = (x | (x >> 2)) & 0x0f0f0f0f0f0f0f0f;
-    x = (x | (x >> 4)) & 0x00ff00ff00ff00ff;
-    x = (x | (x >> 8)) & 0x0000ffff0000ffff;
-    x = (x | (x >> 16)) & 0x00000000ffffffff;
-    x as u32
+    morton::encode2(x, y, 32)
 }
 
 /// deinterleave the bits of X into 32-bit words containing the even and odd
 /// bitlevels of X, respectively.
 fn deinterleave(x: u64) -> (u32, u32) {
-    (squash(x), squash(x >> 1))
+    morton::decode2(x, 32)
 }
 
 #[cfg(test)]
@@ -0,0 +1,124 @@
+//! Maidenhead locators, the grid squares of amateur radio, and their
+//! conversion to geohashes.
+
+use crate::{base32_string, box_cells, enclosing_cell, Box};
+
+/// MAX_PAIRS is the largest number of character pairs of a locator.
+pub const MAX_PAIRS: usize = 10;
+
+/// encode returns the Maidenhead locator of the point (lat, lng) with the
+/// specified number of character pairs (1 to MAX_PAIRS). The first pair is a
+/// field of 20 by 10 degrees, written in upper case. Then pairs of digits
+/// split a cell into 10 by 10 parts and pairs of lower case letters into 24 by
+/// 24 parts, taking turns, so "FN31pr" is a subsquare of 5 by 2.5 minutes.
+pub fn encode(lat: f64, lng: f64, pairs: usize) -> String {
+    let pairs = pairs.clamp(1, MAX_PAIRS);
+    let mut x = ((lng + 180.0) / 360.0).clamp(0.0, 1.0);
+    let mut y = ((lat + 90.0) / 180.0).clamp(0.0, 1.0);
+    let mut locator = String::with_capacity(2 * pairs);
+    for pair in 0..pairs {
+        let n = divisions(pair);
+        x *= n as f64;
+        y *= n as f64;
+        let (i, j) = ((x as usize).min(n - 1), (y as usize).min(n - 1));
+        x -= i as f64;
+        y -= j as f64;
+        locator.push(symbol(pair, i));
+        locator.push(symbol(pair, j));
+    }
+    locator
+}
+
+/// decode returns the region of the Maidenhead locator. Letters may be of
+/// either case.
+pub fn decode(locator: &str) -> Result<Box, String> {
+    validate(locator)?;
+    let (mut min_lng, mut min_lat) = (-180.0, -90.0);
+    let (mut width, mut height) = (360.0, 180.0);
+    for (pair, chars) in locator.as_bytes().chunks(2).enumerate() {
+        let n = divisions(pair) as f64;
+        width /= n;
+        height /= n;
+        min_lng += width * index(pair, chars[0]) as f64;
+        min_lat += height * index(pair, chars[1]) as f64;
+    }
+    Ok(Box {
+        min_lat,
+        max_lat: min_lat + height,
+        min_lng,
+        max_lng: min_lng + width,
+    })
+}
+
+/// validate the Maidenhead locator.
+pub fn validate(locator: &str) -> Result<bool, String> {
+    if locator.is_empty() || locator.len() % 2 == 1 {
+        return Err(format!("odd or zero length {}", locator.len()));
+    }
+    if locator.len() > 2 * MAX_PAIRS {
+        return Err("too long".to_owned());
+    }
+    for (pair, chars) in locator.as_bytes().chunks(2).enumerate() {
+        for &c in chars {
+            if index(pair, c) >= divisions(pair) {
+                return Err(format!("invalid character {}", c));
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// enclosing_hash returns the longest string geohash (max 12 characters) whose
+/// cell contains the whole square of the Maidenhead locator.
+pub fn enclosing_hash(locator: &str) -> Result<String, String> {
+    let (hash, bits) = enclosing_cell(&decode(locator)?);
+    let chars = (bits / 5).min(12);
+    Ok(base32_string(crate::shr(hash, bits - 5 * chars), chars))
+}
+
+/// cover_hashes returns the string geohashes with chars characters (max 12)
+/// whose cells overlap the square of the Maidenhead locator, in row-major order
+/// from the south-west.
+pub fn cover_hashes(locator: &str, chars: usize) -> Result<Vec<String>, String> {
+    if chars > 12 {
+        return Err(format!("too many characters: {}", chars));
+    }
+    let b = decode(locator)?;
+    Ok(box_cells(&b, 5 * chars)
+        .into_iter()
+        .map(|hash| base32_string(hash, chars))
+        .collect())
+}
+
+/// divisions returns the number of parts a cell is split into along each axis
+/// by the pair.
+fn divisions(pair: usize) -> usize {
+    match pair {
+        0 => 18,
+        _ if pair & 1 == 1 => 10,
+        _ => 24,
+    }
+}
+
+fn symbol(pair: usize, i: usize) -> char {
+    match pair {
+        0 => (b'A' + i as u8) as char,
+        _ if pair & 1 == 1 => (b'0' + i as u8) as char,
+        _ => (b'a' + i as u8) as char,
+    }
+}
+
+/// index returns the index of the character of the pair, or usize::MAX when
+/// it is not a valid character for the pair.
+fn index(pair: usize, c: u8) -> usize {
+    let i = if pair & 1 == 1 {
+        c.wrapping_sub(b'0')
+    } else {
+        c.to_ascii_uppercase().wrapping_sub(b'A')
+    };
+    if (i as usize) < divisions(pair) {
+        i as usize
+    } else {
+        usize::MAX
+    }
+}
@@ -0,0 +1,308 @@
+//! Military Grid Reference System (MGRS) references, built on UTM and UPS
+//! coordinates, and their conversion to geohashes.
+
+use crate::distance::normalize_lng;
+use crate::utm::{Hemisphere, Utm, MIN_UTM_LAT};
+use crate::{base32_string, enclosing_cell, shr, Box};
+
+/// MAX_DIGITS is the largest number of digits of the easting and of the
+/// northing of a reference, for squares of 1 meter.
+pub const MAX_DIGITS: usize = 5;
+
+/// Latitude bands of 8 degrees from 80S, the last one stretching to 84N.
+const BANDS: &[u8; 20] = b"CDEFGHJKLMNPQRSTUVWX";
+/// Column letters of the 100 km squares of UTM zones, in sets for zones 1, 2
+/// and 3, repeating.
+const UTM_COLUMNS: [&[u8; 8]; 3] = [b"ABCDEFGH", b"JKLMNPQR", b"STUVWXYZ"];
+/// Row letters of the 100 km squares of UTM zones, which start at F in even
+/// zones.
+const UTM_ROWS: &[u8; 20] = b"ABCDEFGHJKLMNPQRSTUV";
+
+/// UpsBand holds the letters of the 100 km squares of a UPS band, with the
+/// index of the first column and row in units of 100 km.
+struct UpsBand {
+    band: u8,
+    columns: &'static [u8],
+    min_column: usize,
+    rows: &'static [u8],
+    min_row: usize,
+}
+
+const UPS_SOUTH_ROWS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+const UPS_NORTH_ROWS: &[u8] = b"ABCDEFGHJKLMNP";
+const UPS_BANDS: [UpsBand; 4] = [
+    UpsBand {
+        band: b'A',
+        columns: b"JKLPQRSTUXYZ",
+        min_column: 8,
+        rows: UPS_SOUTH_ROWS,
+        min_row: 8,
+    },
+    UpsBand {
+        band: b'B',
+        columns: b"ABCFGHJKLPQR",
+        min_column: 20,
+        rows: UPS_SOUTH_ROWS,
+        min_row: 8,
+    },
+    UpsBand {
+        band: b'Y',
+        columns: b"RSTUXYZ",
+        min_column: 13,
+        rows: UPS_NORTH_ROWS,
+        min_row: 13,
+    },
+    UpsBand {
+        band: b'Z',
+        columns: b"ABCFGHJ",
+        min_column: 20,
+        rows: UPS_NORTH_ROWS,
+        min_row: 13,
+    },
+];
+
+/// encode returns the MGRS reference of the point (lat, lng) with the given
+/// number of digits (0 to MAX_DIGITS) for each of the easting and northing,
+/// as in "33UXP0500444996". No digits give a square of 100 km, and every
+/// digit makes it ten times smaller, down to 1 m.
+pub fn encode(lat: f64, lng: f64, digits: usize) -> String {
+    let digits = digits.min(MAX_DIGITS);
+    let utm = Utm::from_lat_lng(lat, lng);
+    let column = (utm.easting / 100_000.0).floor().max(0.0) as usize;
+    let row = (utm.northing / 100_000.0).floor().max(0.0) as usize;
+    let mut reference = String::with_capacity(5 + 2 * digits);
+    if utm.zone == 0 {
+        let ups = ups_band(utm.hemisphere, utm.easting);
+        reference.push(ups.band as char);
+        reference.push(pick(ups.columns, column, ups.min_column) as char);
+        reference.push(pick(ups.rows, row, ups.min_row) as char);
+    } else {
+        let band = (((lat + 80.0) / 8.0).floor().max(0.0) as usize).min(BANDS.len() - 1);
+        let set = UTM_COLUMNS[(utm.zone as usize - 1) % 3];
+        reference.push_str(&utm.zone.to_string());
+        reference.push(BANDS[band] as char);
+        reference.push(set[(column.max(1) - 1).min(set.len() - 1)] as char);
+        reference.push(UTM_ROWS[(row + row_offset(utm.zone)) % UTM_ROWS.len()] as char);
+    }
+    let scale = 10u64.pow((MAX_DIGITS - digits) as u32);
+    let easting = utm.easting.floor().max(0.0) as u64 % 100_000 / scale;
+    let northing = utm.northing.floor().max(0.0) as u64 % 100_000 / scale;
+    if digits > 0 {
+        reference.push_str(&format!("{:0w$}{:0w$}", easting, northing, w = digits));
+    }
+    reference
+}
+
+/// decode_utm returns the south-west corner of the square of the MGRS
+/// reference as a UTM or UPS coordinate, together with the size of the square
+/// in meters. Spaces are ignored and letters may be of either case.
+pub fn decode_utm(reference: &str) -> Result<(Utm, f64), String> {
+    let reference: Vec<u8> = reference
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let zone_len = reference.iter().take_while(|c| c.is_ascii_digit()).count();
+    if zone_len > 2 || reference.len() < zone_len + 3 {
+        return Err("invalid length".to_owned());
+    }
+    let (band, column, row) = (
+        reference[zone_len],
+        reference[zone_len + 1],
+        reference[zone_len + 2],
+    );
+    let (easting, northing, size) = parse_digits(&reference[zone_len + 3..])?;
+
+    if zone_len == 0 {
+        let ups = UPS_BANDS
+            .iter()
+            .find(|ups| ups.band == band)
+            .ok_or_else(|| format!("invalid band {}", band as char))?;
+        let column = position(ups.columns, column)? + ups.min_column;
+        let row = position(ups.rows, row)? + ups.min_row;
+        let hemisphere = if band < b'N' {
+            Hemisphere::South
+        } else {
+            Hemisphere::North
+        };
+        let utm = Utm {
+            zone: 0,
+            hemisphere,
+            easting: column as f64 * 100_000.0 + easting,
+            northing: row as f64 * 100_000.0 + northing,
+        };
+        return Ok((utm, size));
+    }
+
+    let zone: u8 = std::str::from_utf8(&reference[..zone_len])
+        .unwrap()
+        .parse()
+        .unwrap();
+    if !(1..=60).contains(&zone) {
+        return Err(format!("invalid zone {}", zone));
+    }
+    let band_index = position(BANDS, band)?;
+    let column = position(UTM_COLUMNS[(zone as usize - 1) % 3], column)? + 1;
+    let row = (position(UTM_ROWS, row)? + UTM_ROWS.len() - row_offset(zone)) % UTM_ROWS.len();
+    let band_lat = MIN_UTM_LAT + 8.0 * band_index as f64;
+    let hemisphere = if band_lat < 0.0 {
+        Hemisphere::South
+    } else {
+        Hemisphere::North
+    };
+    // Row letters repeat every 2000 km, so the band picks the cycle. The
+    // northing of the bottom of the band is smallest on the central meridian
+    // in the north and on the edge of the zone in the south.
+    let meridian = 6.0 * zone as f64 - 183.0;
+    let bottom = Utm::from_lat_lng_in_zone(band_lat, meridian, zone)
+        .northing
+        .min(Utm::from_lat_lng_in_zone(band_lat, meridian + 3.0, zone).northing);
+    let bottom = (bottom / 100_000.0).floor() * 100_000.0;
+    let mut northing = row as f64 * 100_000.0 + northing;
+    while northing < bottom {
+        northing += 2_000_000.0;
+    }
+    let utm = Utm {
+        zone,
+        hemisphere,
+        easting: column as f64 * 100_000.0 + easting,
+        northing,
+    };
+    Ok((utm, size))
+}
+
+/// decode returns a box around the square of the MGRS reference. The edges of
+/// the square are not lines of latitude and longitude, so the box holds some
+/// area outside of it. Boxes of squares on the antimeridian have min_lng >
+/// max_lng, and those of squares that hold a pole span every longitude.
+pub fn decode(reference: &str) -> Result<Box, String> {
+    let (utm, size) = decode_utm(reference)?;
+    if utm.zone == 0
+        && (utm.easting..=utm.easting + size).contains(&2_000_000.0)
+        && (utm.northing..=utm.northing + size).contains(&2_000_000.0)
+    {
+        // The corner farthest from the pole is the furthest south or north.
+        let far = |start: f64| {
+            if 2_000_000.0 - start > start + size - 2_000_000.0 {
+                start
+            } else {
+                start + size
+            }
+        };
+        let (far_lat, _) = Utm {
+            easting: far(utm.easting),
+            northing: far(utm.northing),
+            ..utm
+        }
+        .to_lat_lng();
+        let pole = match utm.hemisphere {
+            Hemisphere::North => 90.0,
+            Hemisphere::South => -90.0,
+        };
+        return Ok(Box {
+            min_lat: far_lat.min(pole),
+            max_lat: far_lat.max(pole),
+            min_lng: -180.0,
+            max_lng: 180.0,
+        });
+    }
+
+    // Sample the edges of the square, unwrapping the longitudes around the
+    // first corner.
+    const STEPS: usize = 8;
+    let mut b = Box {
+        min_lat: f64::INFINITY,
+        max_lat: f64::NEG_INFINITY,
+        min_lng: f64::INFINITY,
+        max_lng: f64::NEG_INFINITY,
+    };
+    let mut first_lng = None;
+    for i in 0..4 * STEPS {
+        let t = (i % STEPS) as f64 / STEPS as f64 * size;
+        let (de, dn) = match i / STEPS {
+            0 => (t, 0.0),
+            1 => (size, t),
+            2 => (size - t, size),
+            _ => (0.0, size - t),
+        };
+        let (lat, lng) = Utm {
+            easting: utm.easting + de,
+            northing: utm.northing + dn,
+            ..utm
+        }
+        .to_lat_lng();
+        let first = *first_lng.get_or_insert(lng);
+        let lng = first + normalize_lng(lng - first);
+        b.min_lat = b.min_lat.min(lat);
+        b.max_lat = b.max_lat.max(lat);
+        b.min_lng = b.min_lng.min(lng);
+        b.max_lng = b.max_lng.max(lng);
+    }
+    b.min_lat = b.min_lat.max(-90.0);
+    b.max_lat = b.max_lat.min(90.0);
+    if b.max_lng - b.min_lng < 360.0 {
+        b.min_lng = normalize_lng(b.min_lng);
+        b.max_lng = normalize_lng(b.max_lng);
+        if b.max_lng == -180.0 {
+            b.max_lng = 180.0;
+        }
+    }
+    Ok(b)
+}
+
+/// enclosing_hash returns the longest string geohash (max 12 characters) whose
+/// cell contains the whole square of the MGRS reference.
+pub fn enclosing_hash(reference: &str) -> Result<String, String> {
+    let (hash, bits) = enclosing_cell(&decode(reference)?);
+    let chars = (bits / 5).min(12);
+    Ok(base32_string(shr(hash, bits - 5 * chars), chars))
+}
+
+/// ups_band returns the UPS band of the hemisphere and side of the prime
+/// meridian of the easting.
+fn ups_band(hemisphere: Hemisphere, easting: f64) -> &'static UpsBand {
+    let east = (easting >= 2_000_000.0) as usize;
+    match hemisphere {
+        Hemisphere::South => &UPS_BANDS[east],
+        Hemisphere::North => &UPS_BANDS[2 + east],
+    }
+}
+
+fn row_offset(zone: u8) -> usize {
+    if zone & 1 == 0 {
+        5
+    } else {
+        0
+    }
+}
+
+/// pick returns the letter for the index in units of 100 km, given the index
+/// of the first letter, clamping to the letters there are.
+fn pick(letters: &[u8], index: usize, min: usize) -> u8 {
+    letters[index.saturating_sub(min).min(letters.len() - 1)]
+}
+
+fn position(letters: &[u8], c: u8) -> Result<usize, String> {
+    letters
+        .iter()
+        .position(|&l| l == c)
+        .ok_or_else(|| format!("invalid letter {}", c as char))
+}
+
+/// parse_digits returns the easting and northing in meters within the 100 km
+/// square and the size of the square of the digits.
+fn parse_digits(digits: &[u8]) -> Result<(f64, f64, f64), String> {
+    if digits.len() % 2 == 1 || digits.len() > 2 * MAX_DIGITS {
+        return Err(format!("invalid number of digits {}", digits.len()));
+    }
+    if let Some(&c) = digits.iter().find(|c| !c.is_ascii_digit()) {
+        return Err(format!("invalid character {}", c as char));
+    }
+    let half = digits.len() / 2;
+    let size = 10f64.powi((MAX_DIGITS - half) as i32);
+    let value = |d: &[u8]| -> f64 {
+        let v: u64 = d.iter().fold(0, |v, &c| 10 * v + (c - b'0') as u64);
+        v as f64 * size
+    };
+    Ok((value(&digits[..half]), value(&digits[half..]), size))
+}
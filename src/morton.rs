@@ -0,0 +1,243 @@
+//! Morton (Z-order) keys, which interleave the bits of 2, 3 or 4 coordinates
+//! into a single 64-bit or 128-bit word.
+//!
+//! Bit i of axis a lands at bit dims * i + a of the key, so the first axis
+//! takes the lowest bit. Coordinates are limited to the lowest bits bits, and
+//! a key holds dims * bits bits. Integer geohashes are 2D keys of the latitude
+//! and longitude.
+
+use crate::low_mask64;
+
+/// encode2 interleaves the lowest bits bits (max 32) of x and y into a key,
+/// with x at the even bits and y at the odd bits.
+pub fn encode2(x: u32, y: u32, bits: usize) -> u64 {
+    let mask = low_mask64(bits.min(32)) as u32;
+    spread2(x & mask) | spread2(y & mask) << 1
+}
+
+/// decode2 returns the coordinates (x, y) of the key of bits bits per axis.
+pub fn decode2(key: u64, bits: usize) -> (u32, u32) {
+    let key = key & low_mask64(2 * bits.min(32));
+    (squash2(key), squash2(key >> 1))
+}
+
+/// encode3 interleaves the lowest bits bits (max 21) of x, y and z into a key.
+pub fn encode3(x: u32, y: u32, z: u32, bits: usize) -> u64 {
+    let mask = low_mask64(bits.min(21)) as u32;
+    spread3(x & mask) | spread3(y & mask) << 1 | spread3(z & mask) << 2
+}
+
+/// decode3 returns the coordinates (x, y, z) of the key of bits bits per
+/// axis.
+pub fn decode3(key: u64, bits: usize) -> (u32, u32, u32) {
+    let key = key & low_mask64(3 * bits.min(21));
+    (squash3(key), squash3(key >> 1), squash3(key >> 2))
+}
+
+/// encode4 interleaves the lowest bits bits (max 16) of x, y, z and w into a
+/// key.
+pub fn encode4(x: u16, y: u16, z: u16, w: u16, bits: usize) -> u64 {
+    let mask = low_mask64(bits.min(16)) as u16;
+    spread4(x & mask) | spread4(y & mask) << 1 | spread4(z & mask) << 2 | spread4(w & mask) << 3
+}
+
+/// decode4 returns the coordinates (x, y, z, w) of the key of bits bits per
+/// axis.
+pub fn decode4(key: u64, bits: usize) -> (u16, u16, u16, u16) {
+    let key = key & low_mask64(4 * bits.min(16));
+    (
+        squash4(key),
+        squash4(key >> 1),
+        squash4(key >> 2),
+        squash4(key >> 3),
+    )
+}
+
+/// encode2_128 interleaves the lowest bits bits (max 64) of x and y into a
+/// 128-bit key.
+pub fn encode2_128(x: u64, y: u64, bits: usize) -> u128 {
+    let mask = low_mask128(bits.min(64)) as u64;
+    let (x, y) = (x & mask, y & mask);
+    let low = encode2(x as u32, y as u32, 32);
+    let high = encode2((x >> 32) as u32, (y >> 32) as u32, 32);
+    (high as u128) << 64 | low as u128
+}
+
+/// decode2_128 returns the coordinates (x, y) of the 128-bit key of bits bits
+/// per axis.
+pub fn decode2_128(key: u128, bits: usize) -> (u64, u64) {
+    let key = key & low_mask128(2 * bits.min(64));
+    let (x0, y0) = decode2(key as u64, 32);
+    let (x1, y1) = decode2((key >> 64) as u64, 32);
+    ((x1 as u64) << 32 | x0 as u64, (y1 as u64) << 32 | y0 as u64)
+}
+
+/// encode3_128 interleaves the lowest bits bits (max 42) of x, y and z into a
+/// 128-bit key.
+pub fn encode3_128(x: u64, y: u64, z: u64, bits: usize) -> u128 {
+    let mask = low_mask128(bits.min(42)) as u64;
+    let (x, y, z) = (x & mask, y & mask, z & mask);
+    let low = encode3(x as u32, y as u32, z as u32, 21);
+    let high = encode3((x >> 21) as u32, (y >> 21) as u32, (z >> 21) as u32, 21);
+    (high as u128) << 63 | low as u128
+}
+
+/// decode3_128 returns the coordinates (x, y, z) of the 128-bit key of bits
+/// bits per axis.
+pub fn decode3_128(key: u128, bits: usize) -> (u64, u64, u64) {
+    let key = key & low_mask128(3 * bits.min(42));
+    let (x0, y0, z0) = decode3(key as u64, 21);
+    let (x1, y1, z1) = decode3((key >> 63) as u64, 21);
+    (
+        (x1 as u64) << 21 | x0 as u64,
+        (y1 as u64) << 21 | y0 as u64,
+        (z1 as u64) << 21 | z0 as u64,
+    )
+}
+
+/// encode4_128 interleaves the lowest bits bits (max 32) of x, y, z and w into
+/// a 128-bit key.
+pub fn encode4_128(x: u32, y: u32, z: u32, w: u32, bits: usize) -> u128 {
+    let mask = low_mask128(bits.min(32)) as u32;
+    let (x, y, z, w) = (x & mask, y & mask, z & mask, w & mask);
+    let low = encode4(x as u16, y as u16, z as u16, w as u16, 16);
+    let high = encode4(
+        (x >> 16) as u16,
+        (y >> 16) as u16,
+        (z >> 16) as u16,
+        (w >> 16) as u16,
+        16,
+    );
+    (high as u128) << 64 | low as u128
+}
+
+/// decode4_128 returns the coordinates (x, y, z, w) of the 128-bit key of bits
+/// bits per axis.
+pub fn decode4_128(key: u128, bits: usize) -> (u32, u32, u32, u32) {
+    let key = key & low_mask128(4 * bits.min(32));
+    let (x0, y0, z0, w0) = decode4(key as u64, 16);
+    let (x1, y1, z1, w1) = decode4((key >> 64) as u64, 16);
+    (
+        (x1 as u32) << 16 | x0 as u32,
+        (y1 as u32) << 16 | y0 as u32,
+        (z1 as u32) << 16 | z0 as u32,
+        (w1 as u32) << 16 | w0 as u32,
+    )
+}
+
+/// increment adds one to the coordinate of the axis in the key of dims axes
+/// with bits bits each, without decoding it. The coordinate wraps around to
+/// zero after its largest value.
+pub fn increment(key: u64, dims: usize, bits: usize, axis: usize) -> u64 {
+    let mask = axis_mask(dims, bits, 64, axis) as u64;
+    ((key | !mask).wrapping_add(1) & mask) | (key & !mask)
+}
+
+/// decrement subtracts one from the coordinate of the axis in the key of dims
+/// axes with bits bits each, without decoding it. The coordinate wraps around
+/// to its largest value after zero.
+pub fn decrement(key: u64, dims: usize, bits: usize, axis: usize) -> u64 {
+    let mask = axis_mask(dims, bits, 64, axis) as u64;
+    ((key & mask).wrapping_sub(1) & mask) | (key & !mask)
+}
+
+/// increment_128 is increment for 128-bit keys.
+pub fn increment_128(key: u128, dims: usize, bits: usize, axis: usize) -> u128 {
+    let mask = axis_mask(dims, bits, 128, axis);
+    ((key | !mask).wrapping_add(1) & mask) | (key & !mask)
+}
+
+/// decrement_128 is decrement for 128-bit keys.
+pub fn decrement_128(key: u128, dims: usize, bits: usize, axis: usize) -> u128 {
+    let mask = axis_mask(dims, bits, 128, axis);
+    ((key & mask).wrapping_sub(1) & mask) | (key & !mask)
+}
+
+/// axis_mask returns a word of word_bits bits with the bits of the axis set.
+fn axis_mask(dims: usize, bits: usize, word_bits: usize, axis: usize) -> u128 {
+    assert!(
+        axis < dims,
+        "axis {} out of range for {} dimensions",
+        axis,
+        dims
+    );
+    let bits = bits.min(word_bits / dims);
+    (0..bits).fold(0, |mask, i| mask | 1 << (dims * i + axis))
+}
+
+fn low_mask128(n: usize) -> u128 {
+    if n >= 128 {
+        u128::MAX
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// spread2 spreads out the 32 bits of x into 64 bits, where the bits of x
+/// occupy even bit positions.
+fn spread2(x: u32) -> u64 {
+    let mut x = x as u64;
+    x = (x | (x << 16)) & 0x0000ffff0000ffff;
+    x = (x | (x << 8)) & 0x00ff00ff00ff00ff;
+    x = (x | (x << 4)) & 0x0f0f0f0f0f0f0f0f;
+    x = (x | (x << 2)) & 0x3333333333333333;
+    x = (x | (x << 1)) & 0x5555555555555555;
+    x
+}
+
+/// squash2 squashes the even bit positions of x into a 32-bit word. Odd bit
+/// positions are ignored, and may take any value.
+fn squash2(x: u64) -> u32 {
+    let mut x = x;
+    x &= 0x5555555555555555;
+    x = (x | (x >> 1)) & 0x3333333333333333;
+    x = (x | (x >> 2)) & 0x0f0f0f0f0f0f0f0f;
+    x = (x | (x >> 4)) & 0x00ff00ff00ff00ff;
+    x = (x | (x >> 8)) & 0x0000ffff0000ffff;
+    x = (x | (x >> 16)) & 0x00000000ffffffff;
+    x as u32
+}
+
+/// spread3 spreads out the lowest 21 bits of x into every third bit position.
+fn spread3(x: u32) -> u64 {
+    let mut x = x as u64 & 0x1fffff;
+    x = (x | (x << 32)) & 0x001f00000000ffff;
+    x = (x | (x << 16)) & 0x001f0000ff0000ff;
+    x = (x | (x << 8)) & 0x100f00f00f00f00f;
+    x = (x | (x << 4)) & 0x10c30c30c30c30c3;
+    x = (x | (x << 2)) & 0x1249249249249249;
+    x
+}
+
+/// squash3 squashes every third bit position of x into a 21-bit word.
+fn squash3(x: u64) -> u32 {
+    let mut x = x;
+    x &= 0x1249249249249249;
+    x = (x | (x >> 2)) & 0x10c30c30c30c30c3;
+    x = (x | (x >> 4)) & 0x100f00f00f00f00f;
+    x = (x | (x >> 8)) & 0x001f0000ff0000ff;
+    x = (x | (x >> 16)) & 0x001f00000000ffff;
+    x = (x | (x >> 32)) & 0x00000000001fffff;
+    x as u32
+}
+
+/// spread4 spreads out the 16 bits of x into every fourth bit position.
+fn spread4(x: u16) -> u64 {
+    let mut x = x as u64;
+    x = (x | (x << 24)) & 0x000000ff000000ff;
+    x = (x | (x << 12)) & 0x000f000f000f000f;
+    x = (x | (x << 6)) & 0x0303030303030303;
+    x = (x | (x << 3)) & 0x1111111111111111;
+    x
+}
+
+/// squash4 squashes every fourth bit position of x into a 16-bit word.
+fn squash4(x: u64) -> u16 {
+    let mut x = x;
+    x &= 0x1111111111111111;
+    x = (x | (x >> 3)) & 0x0303030303030303;
+    x = (x | (x >> 6)) & 0x000f000f000f000f;
+    x = (x | (x >> 12)) & 0x000000ff000000ff;
+    x = (x | (x >> 24)) & 0x000000000000ffff;
+    x as u16
+}
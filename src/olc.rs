@@ -0,0 +1,251 @@
+//! Open Location Codes, also known as Plus Codes, and their conversion to
+//! geohashes.
+
+use crate::{base32_string, box_cells, Box};
+
+/// CODE_ALPHABET holds the digits of Open Location Codes.
+const CODE_ALPHABET: &[u8; 20] = b"23456789CFGHJMPQRVWX";
+const SEPARATOR: u8 = b'+';
+const SEPARATOR_POSITION: usize = 8;
+const PADDING: u8 = b'0';
+const ENCODING_BASE: i64 = 20;
+/// PAIR_CODE_LENGTH is the number of digits encoding latitude and longitude
+/// in turns. Later digits pick a cell of a 4 by 5 grid.
+const PAIR_CODE_LENGTH: usize = 10;
+const GRID_COLUMNS: i64 = 4;
+const GRID_ROWS: i64 = 5;
+/// MAX_DIGITS is the largest number of digits of a code.
+pub const MAX_DIGITS: usize = 15;
+const MIN_TRIMMABLE_CODE_LENGTH: usize = 6;
+/// PAIR_PRECISION is the number of steps in a degree after the pair digits.
+const PAIR_PRECISION: i64 = 8000;
+/// LAT_PRECISION and LNG_PRECISION are the number of steps in a degree after
+/// all digits.
+const LAT_PRECISION: i64 = PAIR_PRECISION * 3125;
+const LNG_PRECISION: i64 = PAIR_PRECISION * 1024;
+/// PAIR_RESOLUTIONS are the sizes in degrees of the cells of the pairs.
+const PAIR_RESOLUTIONS: [f64; 5] = [20.0, 1.0, 0.05, 0.0025, 0.000125];
+
+/// encode returns the full Open Location Code of the point (lat, lng) with the
+/// specified number of digits. Lengths are clamped to 2 to MAX_DIGITS, and odd
+/// lengths below 10 are rounded up, since those digits come in pairs. Codes
+/// shorter than 8 digits are padded with zeros, as in "8FVC0000+".
+pub fn encode(lat: f64, lng: f64, code_length: usize) -> String {
+    let mut code_length = code_length.clamp(2, MAX_DIGITS);
+    if code_length < PAIR_CODE_LENGTH && code_length % 2 == 1 {
+        code_length += 1;
+    }
+    let lat_span = 180 * LAT_PRECISION;
+    let lng_span = 360 * LNG_PRECISION;
+    let mut lat_val =
+        ((lat * LAT_PRECISION as f64).floor() as i64 + 90 * LAT_PRECISION).clamp(0, lat_span - 1);
+    let mut lng_val =
+        ((lng * LNG_PRECISION as f64).floor() as i64 + 180 * LNG_PRECISION).rem_euclid(lng_span);
+
+    // Digits are produced from the last one.
+    let mut digits = Vec::with_capacity(MAX_DIGITS);
+    for _ in PAIR_CODE_LENGTH..MAX_DIGITS {
+        let index = (lat_val % GRID_ROWS) * GRID_COLUMNS + lng_val % GRID_COLUMNS;
+        digits.push(CODE_ALPHABET[index as usize]);
+        lat_val /= GRID_ROWS;
+        lng_val /= GRID_COLUMNS;
+    }
+    for _ in 0..PAIR_CODE_LENGTH / 2 {
+        digits.push(CODE_ALPHABET[(lng_val % ENCODING_BASE) as usize]);
+        digits.push(CODE_ALPHABET[(lat_val % ENCODING_BASE) as usize]);
+        lat_val /= ENCODING_BASE;
+        lng_val /= ENCODING_BASE;
+    }
+    digits.reverse();
+    digits.truncate(code_length);
+    digits.resize(digits.len().max(SEPARATOR_POSITION), PADDING);
+    digits.insert(SEPARATOR_POSITION, SEPARATOR);
+    String::from_utf8(digits).unwrap()
+}
+
+/// decode returns the region of the full Open Location Code.
+pub fn decode(code: &str) -> Result<Box, String> {
+    if !is_full(code) {
+        return Err(format!("not a valid full code: {}", code));
+    }
+    let digits = code_digits(code);
+    let pair_digits = digits.len().min(PAIR_CODE_LENGTH);
+
+    let mut lat = -90 * PAIR_PRECISION;
+    let mut lng = -180 * PAIR_PRECISION;
+    let mut place = ENCODING_BASE.pow(4);
+    for i in (0..pair_digits).step_by(2) {
+        if i > 0 {
+            place /= ENCODING_BASE;
+        }
+        lat += digits[i] * place;
+        lng += digits[i + 1] * place;
+    }
+    let mut lat_size = place as f64 / PAIR_PRECISION as f64;
+    let mut lng_size = place as f64 / PAIR_PRECISION as f64;
+
+    let (mut grid_lat, mut grid_lng) = (0, 0);
+    if digits.len() > PAIR_CODE_LENGTH {
+        let mut row_place = GRID_ROWS.pow(4);
+        let mut col_place = GRID_COLUMNS.pow(4);
+        for (i, &digit) in digits[PAIR_CODE_LENGTH..].iter().enumerate() {
+            if i > 0 {
+                row_place /= GRID_ROWS;
+                col_place /= GRID_COLUMNS;
+            }
+            grid_lat += digit / GRID_COLUMNS * row_place;
+            grid_lng += digit % GRID_COLUMNS * col_place;
+        }
+        lat_size = row_place as f64 / LAT_PRECISION as f64;
+        lng_size = col_place as f64 / LNG_PRECISION as f64;
+    }
+    let min_lat = lat as f64 / PAIR_PRECISION as f64 + grid_lat as f64 / LAT_PRECISION as f64;
+    let min_lng = lng as f64 / PAIR_PRECISION as f64 + grid_lng as f64 / LNG_PRECISION as f64;
+    Ok(Box {
+        min_lat,
+        max_lat: min_lat + lat_size,
+        min_lng,
+        max_lng: min_lng + lng_size,
+    })
+}
+
+/// validate the Open Location Code, which may be full or short.
+pub fn validate(code: &str) -> Result<bool, String> {
+    let bytes = code.as_bytes();
+    let sep = match bytes.iter().position(|&c| c == SEPARATOR) {
+        Some(sep) => sep,
+        None => return Err("missing separator".to_owned()),
+    };
+    if bytes.iter().filter(|&&c| c == SEPARATOR).count() > 1 {
+        return Err("more than one separator".to_owned());
+    }
+    if sep > SEPARATOR_POSITION || sep % 2 == 1 || bytes.len() == 1 {
+        return Err(format!("separator in wrong position {}", sep));
+    }
+    if let Some(pad) = bytes.iter().position(|&c| c == PADDING) {
+        let end = bytes.iter().rposition(|&c| c == PADDING).unwrap() + 1;
+        if sep < SEPARATOR_POSITION
+            || pad == 0
+            || (end - pad) % 2 == 1
+            || bytes[pad..end].iter().any(|&c| c != PADDING)
+            || end != sep
+            || sep + 1 != bytes.len()
+        {
+            return Err("invalid padding".to_owned());
+        }
+    }
+    if bytes.len() - sep - 1 == 1 {
+        return Err("single digit after separator".to_owned());
+    }
+    for &c in bytes {
+        if c != SEPARATOR && c != PADDING && digit_value(c).is_none() {
+            return Err(format!("invalid character {}", c));
+        }
+    }
+    Ok(true)
+}
+
+/// is_short decides whether the code is a valid short code, which leaves out
+/// leading digits that must be recovered from a nearby location.
+pub fn is_short(code: &str) -> bool {
+    validate(code).is_ok() && code.find('+').unwrap() < SEPARATOR_POSITION
+}
+
+/// is_full decides whether the code is a valid full code.
+pub fn is_full(code: &str) -> bool {
+    if validate(code).is_err() || is_short(code) {
+        return false;
+    }
+    let bytes = code.as_bytes();
+    let first = digit_value(bytes[0]).unwrap_or(0) * ENCODING_BASE;
+    let second = digit_value(bytes[1]).unwrap_or(0) * ENCODING_BASE;
+    first < 180 && second < 360
+}
+
+/// shorten removes as many leading digits from the full code as can be
+/// recovered from the reference location (lat, lng) with recover_nearest.
+pub fn shorten(code: &str, lat: f64, lng: f64) -> Result<String, String> {
+    if !is_full(code) {
+        return Err(format!("not a valid full code: {}", code));
+    }
+    if code.contains('0') {
+        return Err(format!("cannot shorten padded code: {}", code));
+    }
+    let code = code.to_ascii_uppercase();
+    if code_digits(&code).len() < MIN_TRIMMABLE_CODE_LENGTH {
+        return Err(format!("code too short: {}", code));
+    }
+    let (center_lat, center_lng) = decode(&code)?.center();
+    let lat = lat.clamp(-90.0, 90.0);
+    let lng = crate::distance::normalize_lng(lng);
+    let range = (center_lat - lat).abs().max((center_lng - lng).abs());
+    for i in (1..PAIR_RESOLUTIONS.len() - 1).rev() {
+        // Leave some room for locations that are not quite so close.
+        if range < PAIR_RESOLUTIONS[i] * 0.3 {
+            return Ok(code[(i + 1) * 2..].to_owned());
+        }
+    }
+    Ok(code)
+}
+
+/// recover_nearest returns the full code nearest to the reference location
+/// (lat, lng) that ends with the short code. Full codes are returned as they
+/// are, in upper case.
+pub fn recover_nearest(code: &str, lat: f64, lng: f64) -> Result<String, String> {
+    if !is_short(code) {
+        if is_full(code) {
+            return Ok(code.to_ascii_uppercase());
+        }
+        return Err(format!("not a valid code: {}", code));
+    }
+    let lat = lat.clamp(-90.0, 90.0);
+    let lng = crate::distance::normalize_lng(lng);
+    let code = code.to_ascii_uppercase();
+    let padding = SEPARATOR_POSITION - code.find('+').unwrap();
+    // The size of the cell of the missing digits.
+    let resolution = 20f64.powi(2 - padding as i32 / 2);
+    let half = resolution / 2.0;
+
+    let full = format!("{}{}", &encode(lat, lng, PAIR_CODE_LENGTH)[..padding], code);
+    let digits = code_digits(&full).len();
+    let (mut center_lat, mut center_lng) = decode(&full)?.center();
+    if lat + half < center_lat && center_lat - resolution >= -90.0 {
+        center_lat -= resolution;
+    } else if lat - half > center_lat && center_lat + resolution <= 90.0 {
+        center_lat += resolution;
+    }
+    if lng + half < center_lng {
+        center_lng -= resolution;
+    } else if lng - half > center_lng {
+        center_lng += resolution;
+    }
+    Ok(encode(center_lat, center_lng, digits))
+}
+
+/// to_geohash_cover returns the string geohashes with chars characters (max 12)
+/// whose cells overlap the area of the full code, in row-major order from the
+/// south-west.
+pub fn to_geohash_cover(code: &str, chars: usize) -> Result<Vec<String>, String> {
+    if chars > 12 {
+        return Err(format!("too many characters: {}", chars));
+    }
+    let b = decode(code)?;
+    Ok(box_cells(&b, 5 * chars)
+        .into_iter()
+        .map(|hash| base32_string(hash, chars))
+        .collect())
+}
+
+/// code_digits returns the values of the digits of the code, without the
+/// separator and padding, up to MAX_DIGITS.
+fn code_digits(code: &str) -> Vec<i64> {
+    code.bytes()
+        .filter_map(digit_value)
+        .take(MAX_DIGITS)
+        .collect()
+}
+
+fn digit_value(c: u8) -> Option<i64> {
+    let c = c.to_ascii_uppercase();
+    CODE_ALPHABET.iter().position(|&d| d == c).map(|i| i as i64)
+}
@@ -0,0 +1,263 @@
+//! Outlines of sets of geohash cells as polygons.
+
+use crate::cellunion::IntCellUnion;
+use crate::cover::Polygon;
+use crate::{base32, cell_int_range};
+use std::collections::HashMap;
+
+/// outline returns the boundary of the union of the cells of the string
+/// geohashes as polygons with holes. Edges are traced on the integer grid of
+/// the geohashes, so cells that touch merge exactly, and collinear vertices are
+/// removed. Cells that touch only at a corner belong to separate polygons, as
+/// do cells on either side of the antimeridian.
+pub fn outline<S: AsRef<str>>(hashes: &[S]) -> Vec<Polygon> {
+    let cells: Vec<(u64, usize)> = hashes
+        .iter()
+        .map(|hash| {
+            let hash = hash.as_ref();
+            (base32::decode(hash.as_bytes()), 5 * hash.len())
+        })
+        .collect();
+    outline_int(&cells)
+}
+
+/// outline_int returns the boundary of the union of the cells of the integer
+/// geohashes, each given as (hash, bits), as polygons with holes.
+pub fn outline_int(cells: &[(u64, usize)]) -> Vec<Polygon> {
+    let union = IntCellUnion::new(cells);
+    let rects: Vec<[u64; 4]> = union
+        .iter()
+        .map(|(hash, bits)| {
+            let (lat, lng) = cell_int_range(hash, bits);
+            [
+                lng.0 as u64,
+                lng.1 as u64 + 1,
+                lat.0 as u64,
+                lat.1 as u64 + 1,
+            ]
+        })
+        .collect();
+    if rects.is_empty() {
+        return Vec::new();
+    }
+
+    // Compress the coordinates into a grid where every rectangle covers a
+    // block of grid squares.
+    let xs = compress(rects.iter().flat_map(|r| vec![r[0], r[1]]));
+    let ys = compress(rects.iter().flat_map(|r| vec![r[2], r[3]]));
+    let grid = Grid::new(&xs, &ys, &rects);
+
+    let rings = grid.trace();
+    let (exteriors, holes): (Vec<Ring>, Vec<Ring>) =
+        rings.into_iter().partition(|ring| area2(ring) > 0);
+    let mut polygons: Vec<(i64, Polygon)> = exteriors
+        .iter()
+        .map(|ring| {
+            let polygon = Polygon::new(to_lat_lng(ring, &xs, &ys));
+            (area2(ring), polygon)
+        })
+        .collect();
+
+    // Every hole belongs to the smallest exterior around it.
+    for hole in &holes {
+        let (x, y) = hole_point(hole);
+        let owner = exteriors
+            .iter()
+            .enumerate()
+            .filter(|(_, ring)| ring_contains(ring, x, y))
+            .min_by_key(|(_, ring)| area2(ring))
+            .map(|(i, _)| i);
+        if let Some(i) = owner {
+            polygons[i].1.holes.push(to_lat_lng(hole, &xs, &ys));
+        }
+    }
+    polygons.into_iter().map(|(_, polygon)| polygon).collect()
+}
+
+/// Ring is a closed list of vertices in grid coordinates.
+type Ring = Vec<(i64, i64)>;
+
+/// Grid records which squares of the compressed grid are covered by cells.
+struct Grid {
+    width: usize,
+    height: usize,
+    covered: Vec<bool>,
+}
+
+impl Grid {
+    fn new(xs: &[u64], ys: &[u64], rects: &[[u64; 4]]) -> Grid {
+        let width = xs.len() - 1;
+        let height = ys.len() - 1;
+        let mut covered = vec![false; width * height];
+        for r in rects {
+            let (x0, x1) = (index(xs, r[0]), index(xs, r[1]));
+            let (y0, y1) = (index(ys, r[2]), index(ys, r[3]));
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    covered[y * width + x] = true;
+                }
+            }
+        }
+        Grid {
+            width,
+            height,
+            covered,
+        }
+    }
+
+    fn is_covered(&self, x: i64, y: i64) -> bool {
+        x >= 0
+            && y >= 0
+            && (x as usize) < self.width
+            && (y as usize) < self.height
+            && self.covered[y as usize * self.width + x as usize]
+    }
+
+    /// trace returns the rings around the covered squares. Exterior rings run
+    /// counter-clockwise and holes clockwise, keeping the covered squares on
+    /// the left.
+    fn trace(&self) -> Vec<Ring> {
+        let mut edges: Vec<((i64, i64), (i64, i64))> = Vec::new();
+        for y in 0..self.height as i64 {
+            for x in 0..self.width as i64 {
+                if !self.is_covered(x, y) {
+                    continue;
+                }
+                if !self.is_covered(x, y - 1) {
+                    edges.push(((x, y), (x + 1, y)));
+                }
+                if !self.is_covered(x + 1, y) {
+                    edges.push(((x + 1, y), (x + 1, y + 1)));
+                }
+                if !self.is_covered(x, y + 1) {
+                    edges.push(((x + 1, y + 1), (x, y + 1)));
+                }
+                if !self.is_covered(x - 1, y) {
+                    edges.push(((x, y + 1), (x, y)));
+                }
+            }
+        }
+        let mut outgoing: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for (i, edge) in edges.iter().enumerate() {
+            outgoing.entry(edge.0).or_default().push(i);
+        }
+
+        let mut used = vec![false; edges.len()];
+        let mut rings = Vec::new();
+        for start in 0..edges.len() {
+            if used[start] {
+                continue;
+            }
+            let mut ring = Vec::new();
+            let mut i = start;
+            loop {
+                used[i] = true;
+                let (from, to) = edges[i];
+                ring.push(from);
+                let dir = (to.0 - from.0, to.1 - from.1);
+                // Prefer turning left, so that squares touching only at a
+                // corner end up in separate rings.
+                let next = outgoing[&to]
+                    .iter()
+                    .copied()
+                    .filter(|&j| !used[j])
+                    .max_by_key(|&j| {
+                        let (a, b) = edges[j];
+                        turn(dir, (b.0 - a.0, b.1 - a.1))
+                    });
+                match next {
+                    Some(j) => i = j,
+                    None => break,
+                }
+            }
+            rings.push(simplify(ring));
+        }
+        rings
+    }
+}
+
+/// turn ranks the turn from direction a to direction b: left, straight, right.
+fn turn(a: (i64, i64), b: (i64, i64)) -> i64 {
+    let cross = a.0 * b.1 - a.1 * b.0;
+    if cross > 0 {
+        2
+    } else if cross == 0 {
+        1
+    } else {
+        0
+    }
+}
+
+/// simplify removes the vertices of the ring that lie on a straight line
+/// between their neighbors.
+fn simplify(ring: Ring) -> Ring {
+    let n = ring.len();
+    (0..n)
+        .filter(|&i| {
+            let (p, q, r) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+            (q.0 - p.0) * (r.1 - q.1) - (q.1 - p.1) * (r.0 - q.0) != 0
+        })
+        .map(|i| ring[i])
+        .collect()
+}
+
+/// area2 returns twice the signed area of the ring, positive when it runs
+/// counter-clockwise.
+fn area2(ring: &[(i64, i64)]) -> i64 {
+    let n = ring.len();
+    (0..n)
+        .map(|i| {
+            let (p, q) = (ring[i], ring[(i + 1) % n]);
+            p.0 * q.1 - q.0 * p.1
+        })
+        .sum()
+}
+
+/// hole_point returns a point strictly inside the hole, in doubled grid
+/// coordinates. Holes run clockwise, so the square on the right of the first
+/// edge lies inside.
+fn hole_point(hole: &[(i64, i64)]) -> (i64, i64) {
+    let (p, q) = (hole[0], hole[1]);
+    let (dx, dy) = ((q.0 - p.0).signum(), (q.1 - p.1).signum());
+    // Start of the edge, half a square along it and half a square to the right.
+    (2 * p.0 + dx + dy, 2 * p.1 + dy - dx)
+}
+
+/// ring_contains decides whether the point in doubled grid coordinates lies
+/// inside the ring. The point never lies on the ring.
+fn ring_contains(ring: &[(i64, i64)], x: i64, y: i64) -> bool {
+    let mut inside = false;
+    let n = ring.len();
+    for i in 0..n {
+        let (p, q) = (ring[i], ring[(i + 1) % n]);
+        let (px, py, qx, qy) = (2 * p.0, 2 * p.1, 2 * q.0, 2 * q.1);
+        // Only vertical edges cross the horizontal ray towards +x.
+        if px == qx && px > x && (py > y) != (qy > y) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn compress<I: Iterator<Item = u64>>(values: I) -> Vec<u64> {
+    let mut values: Vec<u64> = values.collect();
+    values.sort_unstable();
+    values.dedup();
+    values
+}
+
+fn index(values: &[u64], value: u64) -> usize {
+    values.binary_search(&value).unwrap()
+}
+
+/// to_lat_lng converts a ring in grid coordinates to (lat, lng) vertices.
+fn to_lat_lng(ring: &[(i64, i64)], xs: &[u64], ys: &[u64]) -> Vec<(f64, f64)> {
+    const SPAN: f64 = 4_294_967_296.0;
+    ring.iter()
+        .map(|&(x, y)| {
+            let lat = ys[y as usize] as f64 / SPAN * 180.0 - 90.0;
+            let lng = xs[x as usize] as f64 / SPAN * 360.0 - 180.0;
+            (lat, lng)
+        })
+        .collect()
+}
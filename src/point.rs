@@ -0,0 +1,174 @@
+//! Points that know which of their coordinates is the latitude, so that they
+//! can be encoded and covered without mixing up (lat, lng) and (lng, lat).
+//!
+//! Bare (f64, f64) tuples do not implement LatLng, since nothing tells their
+//! order. Wrap them in a LatLngTuple or a LngLatTuple, or use a Point, and
+//! passing coordinates in the wrong order becomes a type error.
+
+use crate::cover::Region;
+use crate::Box;
+
+/// LatLng is a point in latitude/longitude space.
+pub trait LatLng {
+    /// lat returns the latitude of the point.
+    fn lat(&self) -> f64;
+
+    /// lng returns the longitude of the point.
+    fn lng(&self) -> f64;
+}
+
+impl<P: LatLng + ?Sized> LatLng for &P {
+    fn lat(&self) -> f64 {
+        (**self).lat()
+    }
+
+    fn lng(&self) -> f64 {
+        (**self).lng()
+    }
+}
+
+/// Point is a point in latitude/longitude space with named coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+impl Point {
+    pub fn new(lat: f64, lng: f64) -> Point {
+        Point { lat, lng }
+    }
+}
+
+impl LatLng for Point {
+    fn lat(&self) -> f64 {
+        self.lat
+    }
+
+    fn lng(&self) -> f64 {
+        self.lng
+    }
+}
+
+/// LatLngTuple is a point given as (lat, lng), the order of this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LatLngTuple(pub f64, pub f64);
+
+impl LatLng for LatLngTuple {
+    fn lat(&self) -> f64 {
+        self.0
+    }
+
+    fn lng(&self) -> f64 {
+        self.1
+    }
+}
+
+/// LngLatTuple is a point given as (lng, lat), the (x, y) order of GeoJSON
+/// and most projections.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LngLatTuple(pub f64, pub f64);
+
+impl LatLng for LngLatTuple {
+    fn lat(&self) -> f64 {
+        self.1
+    }
+
+    fn lng(&self) -> f64 {
+        self.0
+    }
+}
+
+impl From<LatLngTuple> for Point {
+    fn from(p: LatLngTuple) -> Point {
+        Point::new(p.0, p.1)
+    }
+}
+
+impl From<LngLatTuple> for Point {
+    fn from(p: LngLatTuple) -> Point {
+        Point::new(p.1, p.0)
+    }
+}
+
+impl From<Point> for LatLngTuple {
+    fn from(p: Point) -> LatLngTuple {
+        LatLngTuple(p.lat, p.lng)
+    }
+}
+
+impl From<Point> for LngLatTuple {
+    fn from(p: Point) -> LngLatTuple {
+        LngLatTuple(p.lng, p.lat)
+    }
+}
+
+/// A point as a region is covered by the cells that hold it.
+impl Region for Point {
+    fn bounding_box(&self) -> Box {
+        Box {
+            min_lat: self.lat,
+            max_lat: self.lat,
+            min_lng: self.lng,
+            max_lng: self.lng,
+        }
+    }
+
+    fn contains_box(&self, _: &Box) -> bool {
+        false
+    }
+
+    fn intersects_box(&self, b: &Box) -> bool {
+        b.contains(self.lat, self.lng)
+    }
+}
+
+/// encode encodes the point to a string geohash with the standard 12
+/// characters of precision.
+pub fn encode<P: LatLng>(p: P) -> String {
+    crate::encode(p.lat(), p.lng())
+}
+
+/// encode_with_precision encodes the point to a string geohash with the
+/// specified number of characters of precision (max 12).
+pub fn encode_with_precision<P: LatLng>(p: P, chars: usize) -> String {
+    crate::encode_with_precision(p.lat(), p.lng(), chars)
+}
+
+/// encode_int encodes the point to a 64-bit integer geohash.
+pub fn encode_int<P: LatLng>(p: P) -> u64 {
+    crate::encode_int(p.lat(), p.lng())
+}
+
+/// encode_int_with_precision encodes the point to an integer geohash with the
+/// specified number of bits.
+pub fn encode_int_with_precision<P: LatLng>(p: P, bits: usize) -> u64 {
+    crate::encode_int_with_precision(p.lat(), p.lng(), bits)
+}
+
+/// decode decodes the string geohash to a point.
+pub fn decode(hash: &str) -> Point {
+    let (lat, lng) = crate::decode(hash);
+    Point::new(lat, lng)
+}
+
+/// decode_center decodes the string geohash to the point at the center of its
+/// box.
+pub fn decode_center(hash: &str) -> Point {
+    let (lat, lng) = crate::decode_center(hash);
+    Point::new(lat, lng)
+}
+
+/// neighbors returns the string geohashes with chars characters of the
+/// neighbors of the cell that holds the point, in the order of the
+/// directions.
+pub fn neighbors<P: LatLng>(p: P, chars: usize) -> [String; 8] {
+    crate::neighbors(&encode_with_precision(p, chars))
+}
+
+/// neighbors_int_with_precision returns the integer geohashes with bits of
+/// precision of the neighbors of the cell that holds the point, in the order
+/// of the directions.
+pub fn neighbors_int_with_precision<P: LatLng>(p: P, bits: usize) -> [u64; 8] {
+    crate::neighbors_int_with_precision(encode_int_with_precision(p, bits), bits)
+}
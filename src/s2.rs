@@ -0,0 +1,477 @@
+//! S2 cell IDs: the cells of a cube projected onto the sphere, numbered along
+//! a Hilbert curve on each of its six faces, and their conversion to and from
+//! geohashes.
+
+use crate::cover::{cover, CoverOptions, Region};
+use crate::hilbert::{IJ_TO_POS, POS_TO_IJ, POS_TO_ORIENTATION, SWAP_MASK};
+use crate::Box;
+
+/// MAX_LEVEL is the level of the smallest S2 cells, the leaves.
+pub const MAX_LEVEL: usize = 30;
+
+/// Number of bits of the position along the Hilbert curve of a face.
+const POS_BITS: usize = 2 * MAX_LEVEL + 1;
+/// Number of leaf cells along each side of a face.
+const MAX_SIZE: u32 = 1 << MAX_LEVEL;
+
+/// CellId identifies an S2 cell by its face, in the top 3 bits, and its
+/// position along the Hilbert curve of the face, followed by a 1 bit that
+/// marks the level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CellId(pub u64);
+
+impl CellId {
+    /// from_face returns the cell of the whole face (0 to 5).
+    pub fn from_face(face: u8) -> CellId {
+        CellId(((face as u64) << POS_BITS) | 1 << (POS_BITS - 1))
+    }
+
+    /// from_face_ij returns the leaf cell at (i, j) of the face, where i and j
+    /// count leaves from 0 to 2^30 - 1.
+    pub fn from_face_ij(face: u8, i: u32, j: u32) -> CellId {
+        let mut id = (face as u64) << (POS_BITS - 1);
+        let mut orientation = face as usize & SWAP_MASK;
+        for k in (0..MAX_LEVEL).rev() {
+            let ij = ((i >> k) & 1) << 1 | (j >> k) & 1;
+            let pos = IJ_TO_POS[orientation][ij as usize];
+            id |= (pos as u64) << (2 * k);
+            orientation ^= POS_TO_ORIENTATION[pos];
+        }
+        CellId(id << 1 | 1)
+    }
+
+    /// from_lat_lng returns the leaf cell that contains the point (lat, lng).
+    pub fn from_lat_lng(lat: f64, lng: f64) -> CellId {
+        let (face, u, v) = xyz_to_face_uv(lat_lng_to_xyz(lat, lng));
+        let i = st_to_ij(uv_to_st(u));
+        let j = st_to_ij(uv_to_st(v));
+        CellId::from_face_ij(face, i, j)
+    }
+
+    /// from_lat_lng_at_level returns the cell at the level (0 to MAX_LEVEL)
+    /// that contains the point (lat, lng).
+    pub fn from_lat_lng_at_level(lat: f64, lng: f64, level: usize) -> CellId {
+        CellId::from_lat_lng(lat, lng).parent(level)
+    }
+
+    /// from_token returns the cell of the token, the hexadecimal form of the
+    /// ID without trailing zeros.
+    pub fn from_token(token: &str) -> Result<CellId, String> {
+        if token.is_empty() || token.len() > 16 {
+            return Err(format!("invalid token length {}", token.len()));
+        }
+        let id = u64::from_str_radix(token, 16).map_err(|err| err.to_string())?;
+        let id = CellId(id << (4 * (16 - token.len())));
+        if !id.is_valid() {
+            return Err(format!("invalid cell {}", token));
+        }
+        Ok(id)
+    }
+
+    /// to_token returns the hexadecimal form of the ID without trailing zeros.
+    pub fn to_token(&self) -> String {
+        if self.0 == 0 {
+            return "X".to_owned();
+        }
+        let hex = format!("{:016x}", self.0);
+        hex.trim_end_matches('0').to_owned()
+    }
+
+    /// is_valid decides whether the ID names a cell: a face of 0 to 5 and a
+    /// marker bit at an even distance from the end, below the face bits.
+    pub fn is_valid(&self) -> bool {
+        self.face() < 6 && self.lsb() & 0x1555_5555_5555_5555 != 0
+    }
+
+    /// face returns the face of the cell, 0 to 5.
+    pub fn face(&self) -> u8 {
+        (self.0 >> POS_BITS) as u8
+    }
+
+    /// level returns the level of the cell, 0 for faces to MAX_LEVEL for
+    /// leaves.
+    pub fn level(&self) -> usize {
+        MAX_LEVEL - self.0.trailing_zeros() as usize / 2
+    }
+
+    /// is_leaf decides whether the cell is at MAX_LEVEL.
+    pub fn is_leaf(&self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    /// parent returns the cell at the level that contains the cell. Levels
+    /// below the level of the cell return the cell itself.
+    pub fn parent(&self, level: usize) -> CellId {
+        if level >= self.level() {
+            return *self;
+        }
+        let lsb = 1u64 << (2 * (MAX_LEVEL - level));
+        CellId((self.0 & lsb.wrapping_neg()) | lsb)
+    }
+
+    /// children returns the four cells one level below the cell, in Hilbert
+    /// curve order. Leaves have no children and return themselves.
+    pub fn children(&self) -> [CellId; 4] {
+        if self.is_leaf() {
+            return [*self; 4];
+        }
+        let lsb = self.lsb() >> 2;
+        let first = self.0 - self.lsb() + lsb;
+        [
+            CellId(first),
+            CellId(first + 2 * lsb),
+            CellId(first + 4 * lsb),
+            CellId(first + 6 * lsb),
+        ]
+    }
+
+    /// range_min returns the first leaf cell within the cell.
+    pub fn range_min(&self) -> CellId {
+        CellId(self.0 - (self.lsb() - 1))
+    }
+
+    /// range_max returns the last leaf cell within the cell.
+    pub fn range_max(&self) -> CellId {
+        CellId(self.0 + (self.lsb() - 1))
+    }
+
+    /// contains decides whether the other cell lies within the cell.
+    pub fn contains(&self, other: &CellId) -> bool {
+        self.range_min() <= *other && *other <= self.range_max()
+    }
+
+    /// center returns the point (lat, lng) at the center of the cell.
+    pub fn center(&self) -> (f64, f64) {
+        let (face, u, v) = self.uv_bounds();
+        let s = (uv_to_st(u.0) + uv_to_st(u.1)) / 2.0;
+        let t = (uv_to_st(v.0) + uv_to_st(v.1)) / 2.0;
+        xyz_to_lat_lng(face_uv_to_xyz(face, st_to_uv(s), st_to_uv(t)))
+    }
+
+    /// vertices returns the four corners (lat, lng) of the cell in
+    /// counter-clockwise order. The edges between them are great circles.
+    pub fn vertices(&self) -> [(f64, f64); 4] {
+        let (face, u, v) = self.uv_bounds();
+        [(u.0, v.0), (u.1, v.0), (u.1, v.1), (u.0, v.1)]
+            .map(|(u, v)| xyz_to_lat_lng(face_uv_to_xyz(face, u, v)))
+    }
+
+    /// bounding_box returns the smallest box around the cell. Boxes of cells
+    /// that cross the antimeridian have min_lng > max_lng, and those of cells
+    /// that hold a pole span every longitude.
+    pub fn bounding_box(&self) -> Box {
+        let face = self.face();
+        if self.level() == 0 {
+            let lat = (1.0f64 / 3.0).sqrt().asin().to_degrees();
+            return match face {
+                2 => Box {
+                    min_lat: lat,
+                    max_lat: 90.0,
+                    min_lng: -180.0,
+                    max_lng: 180.0,
+                },
+                5 => Box {
+                    min_lat: -90.0,
+                    max_lat: -lat,
+                    min_lng: -180.0,
+                    max_lng: 180.0,
+                },
+                _ => {
+                    let center = [0.0, 90.0, 0.0, 180.0, -90.0][face as usize];
+                    Box {
+                        min_lat: -45.0,
+                        max_lat: 45.0,
+                        min_lng: crate::distance::normalize_lng(center - 45.0),
+                        max_lng: crate::distance::normalize_lng(center + 45.0),
+                    }
+                }
+            };
+        }
+        // Below the faces, the extremes of latitude are at one pair of
+        // opposite corners and those of longitude at the other pair.
+        let (_, u, v) = self.uv_bounds();
+        let corner = |i: bool, j: bool| {
+            let u = if i { u.1 } else { u.0 };
+            let v = if j { v.1 } else { v.0 };
+            xyz_to_lat_lng(face_uv_to_xyz(face, u, v))
+        };
+        let (u_axis_z, v_axis_z) = AXIS_Z[face as usize];
+        let (us, vs) = (u.0 + u.1, v.0 + v.1);
+        let i = if u_axis_z == 0.0 { us < 0.0 } else { us > 0.0 };
+        let j = if v_axis_z == 0.0 { vs < 0.0 } else { vs > 0.0 };
+        let (lat1, lat2) = (corner(i, j).0, corner(!i, !j).0);
+        let (min_lat, max_lat) = (lat1.min(lat2), lat1.max(lat2));
+        if max_lat >= 90.0 - 1e-12 || min_lat <= -90.0 + 1e-12 {
+            return Box {
+                min_lat: min_lat.max(-90.0),
+                max_lat: max_lat.min(90.0),
+                min_lng: -180.0,
+                max_lng: 180.0,
+            };
+        }
+        // The longitudes span the shorter way between the pair.
+        let (a, b) = (corner(i, !j).1, corner(!i, j).1);
+        let (min_lng, max_lng) = if (b - a).rem_euclid(360.0) <= 180.0 {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        Box {
+            min_lat,
+            max_lat,
+            min_lng,
+            max_lng,
+        }
+    }
+
+    fn lsb(&self) -> u64 {
+        self.0 & self.0.wrapping_neg()
+    }
+
+    /// uv_bounds returns the face and the ranges of u and v of the cell.
+    fn uv_bounds(&self) -> (u8, (f64, f64), (f64, f64)) {
+        let (face, i, j, size) = self.face_ij_bounds();
+        let range = |x: u64| {
+            (
+                st_to_uv(x as f64 / MAX_SIZE as f64),
+                st_to_uv((x + size) as f64 / MAX_SIZE as f64),
+            )
+        };
+        (face, range(i as u64), range(j as u64))
+    }
+
+    /// face_ij_bounds returns the face, the (i, j) of the lowest leaf and the
+    /// number of leaves along each side of the cell.
+    fn face_ij_bounds(&self) -> (u8, u32, u32, u64) {
+        let face = self.face();
+        let level = self.level();
+        let mut orientation = face as usize & SWAP_MASK;
+        let (mut i, mut j) = (0u32, 0u32);
+        for k in 1..=level {
+            let pos = ((self.0 >> (POS_BITS - 2 * k)) & 3) as usize;
+            let ij = POS_TO_IJ[orientation][pos];
+            i = i << 1 | (ij >> 1) as u32;
+            j = j << 1 | (ij & 1) as u32;
+            orientation ^= POS_TO_ORIENTATION[pos];
+        }
+        let shift = MAX_LEVEL - level;
+        let size = 1u64 << shift;
+        if shift == MAX_LEVEL {
+            return (face, 0, 0, size);
+        }
+        (face, i << shift, j << shift, size)
+    }
+}
+
+/// The z components of the u and v axes of each face.
+const AXIS_Z: [(f64, f64); 6] = [
+    (0.0, 1.0),
+    (0.0, 1.0),
+    (0.0, 0.0),
+    (-1.0, 0.0),
+    (-1.0, 0.0),
+    (0.0, 0.0),
+];
+
+/// cells_for_hash returns the S2 cells at the level whose regions may overlap
+/// the cell of the string geohash, in ID order. Together they cover it.
+pub fn cells_for_hash(hash: &str, level: usize) -> Vec<CellId> {
+    cells_for_box(&crate::bounding_box(hash), level)
+}
+
+/// cells_for_box returns the S2 cells at the level whose regions may overlap
+/// the box, in ID order.
+pub fn cells_for_box(b: &Box, level: usize) -> Vec<CellId> {
+    let level = level.min(MAX_LEVEL);
+    let mut result = Vec::new();
+    let mut stack: Vec<CellId> = (0..6).rev().map(CellId::from_face).collect();
+    while let Some(cell) = stack.pop() {
+        if !cell.intersects_box(b) {
+            continue;
+        }
+        if cell.level() == level {
+            result.push(cell);
+        } else {
+            let children = cell.children();
+            stack.extend(children.iter().rev());
+        }
+    }
+    result
+}
+
+/// hashes_for_cell returns the string geohashes with chars characters whose
+/// cells may overlap the S2 cell, in sorted order. Together they cover it.
+pub fn hashes_for_cell(cell: &CellId, chars: usize) -> Vec<String> {
+    let options = CoverOptions {
+        min_level: chars,
+        max_level: chars,
+        max_cells: usize::MAX,
+    };
+    cover(cell, &options)
+}
+
+impl Region for CellId {
+    fn bounding_box(&self) -> Box {
+        CellId::bounding_box(self)
+    }
+
+    fn contains_box(&self, b: &Box) -> bool {
+        match box_uv_bounds(b, self.face()) {
+            Some((u, v)) => {
+                let (_, cu, cv) = self.uv_bounds();
+                cu.0 <= u.0 && u.1 <= cu.1 && cv.0 <= v.0 && v.1 <= cv.1
+            }
+            None => false,
+        }
+    }
+
+    fn intersects_box(&self, b: &Box) -> bool {
+        if !boxes_intersect(&CellId::bounding_box(self), b) {
+            return false;
+        }
+        match box_uv_bounds(b, self.face()) {
+            Some((u, v)) => {
+                let (_, cu, cv) = self.uv_bounds();
+                cu.0 <= u.1 && u.0 <= cu.1 && cv.0 <= v.1 && v.0 <= cv.1
+            }
+            None => true,
+        }
+    }
+}
+
+/// boxes_intersect decides whether the boxes have any point in common, where
+/// either may cross the antimeridian.
+fn boxes_intersect(a: &Box, b: &Box) -> bool {
+    let split = |b: &Box| -> Vec<Box> {
+        if b.min_lng <= b.max_lng {
+            vec![*b]
+        } else {
+            vec![
+                Box {
+                    max_lng: 180.0,
+                    ..*b
+                },
+                Box {
+                    min_lng: -180.0,
+                    ..*b
+                },
+            ]
+        }
+    };
+    split(a)
+        .iter()
+        .any(|a| split(b).iter().any(|b| a.intersects(b)))
+}
+
+/// box_uv_bounds returns the ranges of u and v of the box on the face, when
+/// the whole box lies on the side of the sphere facing the face. The extremes
+/// are attained on the edges of the box, at the corners, where the parallels
+/// cross the meridians of the face centers, or where the meridians cross the
+/// equator.
+fn box_uv_bounds(b: &Box, face: u8) -> Option<((f64, f64), (f64, f64))> {
+    if b.min_lng > b.max_lng {
+        return None;
+    }
+    let mut points = vec![
+        (b.min_lat, b.min_lng),
+        (b.min_lat, b.max_lng),
+        (b.max_lat, b.min_lng),
+        (b.max_lat, b.max_lng),
+    ];
+    for lng in [-180.0, -90.0, 0.0, 90.0, 180.0] {
+        if b.min_lng < lng && lng < b.max_lng {
+            points.push((b.min_lat, lng));
+            points.push((b.max_lat, lng));
+        }
+    }
+    if b.min_lat < 0.0 && 0.0 < b.max_lat {
+        points.push((0.0, b.min_lng));
+        points.push((0.0, b.max_lng));
+    }
+    let mut u = (f64::INFINITY, f64::NEG_INFINITY);
+    let mut v = (f64::INFINITY, f64::NEG_INFINITY);
+    for (lat, lng) in points {
+        let p = lat_lng_to_xyz(lat, lng);
+        let axis = face as usize % 3;
+        let sign = if face < 3 { 1.0 } else { -1.0 };
+        if p[axis] * sign <= 1e-15 {
+            return None;
+        }
+        let (pu, pv) = xyz_to_uv(face, p);
+        u = (u.0.min(pu), u.1.max(pu));
+        v = (v.0.min(pv), v.1.max(pv));
+    }
+    Some((u, v))
+}
+
+fn lat_lng_to_xyz(lat: f64, lng: f64) -> [f64; 3] {
+    let (lat, lng) = (lat.to_radians(), lng.to_radians());
+    [lat.cos() * lng.cos(), lat.cos() * lng.sin(), lat.sin()]
+}
+
+fn xyz_to_lat_lng(p: [f64; 3]) -> (f64, f64) {
+    let lat = p[2].atan2(p[0].hypot(p[1]));
+    let lng = p[1].atan2(p[0]);
+    (lat.to_degrees(), lng.to_degrees())
+}
+
+/// xyz_to_face_uv returns the face the point projects onto, the one of its
+/// largest component, and its (u, v) on the face.
+fn xyz_to_face_uv(p: [f64; 3]) -> (u8, f64, f64) {
+    let abs = p.map(f64::abs);
+    let mut axis = if abs[0] > abs[1] { 0 } else { 1 };
+    if abs[2] > abs[axis] {
+        axis = 2;
+    }
+    let face = if p[axis] < 0.0 { axis + 3 } else { axis } as u8;
+    let (u, v) = xyz_to_uv(face, p);
+    (face, u, v)
+}
+
+/// xyz_to_uv returns (u, v) of the point projected onto the face.
+fn xyz_to_uv(face: u8, p: [f64; 3]) -> (f64, f64) {
+    let [x, y, z] = p;
+    match face {
+        0 => (y / x, z / x),
+        1 => (-x / y, z / y),
+        2 => (-x / z, -y / z),
+        3 => (z / x, y / x),
+        4 => (z / y, -x / y),
+        _ => (-y / z, -x / z),
+    }
+}
+
+fn face_uv_to_xyz(face: u8, u: f64, v: f64) -> [f64; 3] {
+    match face {
+        0 => [1.0, u, v],
+        1 => [-u, 1.0, v],
+        2 => [-u, -v, 1.0],
+        3 => [-1.0, -v, -u],
+        4 => [v, -1.0, -u],
+        _ => [v, u, -1.0],
+    }
+}
+
+/// uv_to_st applies the quadratic transform that makes cells of a level
+/// closer in size.
+fn uv_to_st(u: f64) -> f64 {
+    if u >= 0.0 {
+        0.5 * (1.0 + 3.0 * u).sqrt()
+    } else {
+        1.0 - 0.5 * (1.0 - 3.0 * u).sqrt()
+    }
+}
+
+fn st_to_uv(s: f64) -> f64 {
+    if s >= 0.5 {
+        (4.0 * s * s - 1.0) / 3.0
+    } else {
+        (1.0 - 4.0 * (1.0 - s) * (1.0 - s)) / 3.0
+    }
+}
+
+fn st_to_ij(s: f64) -> u32 {
+    (MAX_SIZE as f64 * s)
+        .floor()
+        .clamp(0.0, (MAX_SIZE - 1) as f64) as u32
+}
@@ -0,0 +1,244 @@
+//! Geohashes within a custom rectangular extent, such as the projected
+//! coordinates of a map in meters, an indoor floor plan or a game world.
+//!
+//! A GeohashSpace splits its extent the way the global API splits the world,
+//! with x taking the place of the longitude and y that of the latitude. Boxes
+//! hold y in their latitude fields and x in their longitude fields. The
+//! default space is the world, where every function gives the same results as
+//! the global API.
+
+use crate::cover::{self, CoverOptions, Region};
+use crate::{
+    base32, base32_string, decode_extent, deinterleave, encode_extent, error_with_precision,
+    interleave, shl, Box,
+};
+
+/// GeohashSpace is the extent from (min_x, min_y) to (max_x, max_y) that
+/// geohashes divide.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeohashSpace {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl Default for GeohashSpace {
+    fn default() -> GeohashSpace {
+        GeohashSpace {
+            min_x: -180.0,
+            min_y: -90.0,
+            max_x: 180.0,
+            max_y: 90.0,
+        }
+    }
+}
+
+impl GeohashSpace {
+    /// new returns the space of the extent, which must have finite bounds with
+    /// the minimums below the maximums.
+    pub fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Result<GeohashSpace, String> {
+        if ![min_x, min_y, max_x, max_y].iter().all(|v| v.is_finite()) {
+            return Err("extent is not finite".to_owned());
+        }
+        if !(min_x < max_x && min_y < max_y) {
+            return Err("extent is empty".to_owned());
+        }
+        Ok(GeohashSpace {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        })
+    }
+
+    /// extent returns the whole space as a box.
+    pub fn extent(&self) -> Box {
+        Box {
+            min_lat: self.min_y,
+            max_lat: self.max_y,
+            min_lng: self.min_x,
+            max_lng: self.max_x,
+        }
+    }
+
+    /// encode encodes the point (x, y) to a string geohash of 12 characters.
+    pub fn encode(&self, x: f64, y: f64) -> String {
+        self.encode_with_precision(x, y, 12)
+    }
+
+    /// encode_with_precision encodes the point (x, y) to a string geohash with
+    /// the specified number of characters of precision (max 12).
+    pub fn encode_with_precision(&self, x: f64, y: f64, chars: usize) -> String {
+        let bits = 5 * chars;
+        let inthash = self.encode_int_with_precision(x, y, bits);
+        base32_string(inthash, chars)
+    }
+
+    /// encode_int encodes the point (x, y) to a 64-bit integer geohash.
+    pub fn encode_int(&self, x: f64, y: f64) -> u64 {
+        let y_int = encode_extent(y, self.min_y, self.max_y);
+        let x_int = encode_extent(x, self.min_x, self.max_x);
+        interleave(y_int, x_int)
+    }
+
+    /// encode_int_with_precision encodes the point (x, y) to an integer with
+    /// the specified number of bits.
+    pub fn encode_int_with_precision(&self, x: f64, y: f64, bits: usize) -> u64 {
+        let hash = self.encode_int(x, y);
+        hash >> (64 - bits)
+    }
+
+    /// error_with_precision returns the height and width of the cells of
+    /// integer geohashes with bits of precision.
+    pub fn error_with_precision(&self, bits: usize) -> (f64, f64) {
+        let (lat_err, lng_err) = error_with_precision(bits);
+        (
+            lat_err / 180.0 * (self.max_y - self.min_y),
+            lng_err / 360.0 * (self.max_x - self.min_x),
+        )
+    }
+
+    /// bounding_box returns the region of the string geohash.
+    pub fn bounding_box(&self, hash: &str) -> Box {
+        let bits = 5 * hash.len();
+        let inthash = base32::decode(hash.as_bytes());
+        self.bounding_box_int_with_precision(inthash, bits)
+    }
+
+    /// bounding_box_int_with_precision returns the region of the integer
+    /// geohash with the specified precision.
+    pub fn bounding_box_int_with_precision(&self, hash: u64, bits: usize) -> Box {
+        let full_hash = shl(hash, 64 - bits);
+        let (y_int, x_int) = deinterleave(full_hash);
+        let y = decode_extent(y_int, self.min_y, self.max_y);
+        let x = decode_extent(x_int, self.min_x, self.max_x);
+        let (y_err, x_err) = self.error_with_precision(bits);
+        Box {
+            min_lat: y,
+            max_lat: y + y_err,
+            min_lng: x,
+            max_lng: x + x_err,
+        }
+    }
+
+    /// decode decodes the string geohash to a (x, y) point.
+    pub fn decode(&self, hash: &str) -> (f64, f64) {
+        let (y, x) = self.bounding_box(hash).round();
+        (x, y)
+    }
+
+    /// decode_center decodes the string geohash to the central point (x, y)
+    /// of its box.
+    pub fn decode_center(&self, hash: &str) -> (f64, f64) {
+        let (y, x) = self.bounding_box(hash).center();
+        (x, y)
+    }
+
+    /// decode_int_with_precision decodes the integer geohash with bits of
+    /// precision to a (x, y) point.
+    pub fn decode_int_with_precision(&self, hash: u64, bits: usize) -> (f64, f64) {
+        let (y, x) = self.bounding_box_int_with_precision(hash, bits).round();
+        (x, y)
+    }
+
+    /// neighbors returns the string geohashes of the neighbors of the hash, in
+    /// the order of the directions. Cells on the edge of the extent are their
+    /// own neighbors beyond it.
+    pub fn neighbors(&self, hash: &str) -> [String; 8] {
+        let chars = hash.len();
+        let inthash = base32::decode(hash.as_bytes());
+        self.neighbors_int_with_precision(inthash, 5 * chars)
+            .map(|n| base32_string(n, chars))
+    }
+
+    /// neighbors_int_with_precision returns the integer geohashes of the
+    /// neighbors of the hash at the given precision, in the order of the
+    /// directions.
+    pub fn neighbors_int_with_precision(&self, hash: u64, bits: usize) -> [u64; 8] {
+        let b = self.bounding_box_int_with_precision(hash, bits);
+        let (y, x) = b.center();
+        let dy = b.max_lat - b.min_lat;
+        let dx = b.max_lng - b.min_lng;
+        [
+            // N
+            self.encode_int_with_precision(x, y + dy, bits),
+            // NE,
+            self.encode_int_with_precision(x + dx, y + dy, bits),
+            // E,
+            self.encode_int_with_precision(x + dx, y, bits),
+            // SE,
+            self.encode_int_with_precision(x + dx, y - dy, bits),
+            // S,
+            self.encode_int_with_precision(x, y - dy, bits),
+            // SW,
+            self.encode_int_with_precision(x - dx, y - dy, bits),
+            // W,
+            self.encode_int_with_precision(x - dx, y, bits),
+            // NW
+            self.encode_int_with_precision(x - dx, y + dy, bits),
+        ]
+    }
+
+    /// cover returns string geohash cells that together cover the whole
+    /// region, given in the coordinates of the space, as cover::cover does for
+    /// the world.
+    pub fn cover<R: Region + ?Sized>(&self, region: &R, options: &CoverOptions) -> Vec<String> {
+        cover::cover(&InSpace(self, region), options)
+    }
+
+    /// interior_cover returns string geohash cells that lie completely within
+    /// the region, given in the coordinates of the space, as
+    /// cover::interior_cover does for the world.
+    pub fn interior_cover<R: Region + ?Sized>(
+        &self,
+        region: &R,
+        options: &CoverOptions,
+    ) -> Vec<String> {
+        cover::interior_cover(&InSpace(self, region), options)
+    }
+
+    /// space_box returns the box of the space at the place of the box of the
+    /// world.
+    fn space_box(&self, b: &Box) -> Box {
+        let y = |lat: f64| self.min_y + (self.max_y - self.min_y) * ((lat + 90.0) / 180.0);
+        let x = |lng: f64| self.min_x + (self.max_x - self.min_x) * ((lng + 180.0) / 360.0);
+        Box {
+            min_lat: y(b.min_lat),
+            max_lat: y(b.max_lat),
+            min_lng: x(b.min_lng),
+            max_lng: x(b.max_lng),
+        }
+    }
+
+    /// world_box returns the box of the world at the place of the box of the
+    /// space.
+    fn world_box(&self, b: &Box) -> Box {
+        let lat = |y: f64| (y - self.min_y) / (self.max_y - self.min_y) * 180.0 - 90.0;
+        let lng = |x: f64| (x - self.min_x) / (self.max_x - self.min_x) * 360.0 - 180.0;
+        Box {
+            min_lat: lat(b.min_lat),
+            max_lat: lat(b.max_lat),
+            min_lng: lng(b.min_lng),
+            max_lng: lng(b.max_lng),
+        }
+    }
+}
+
+/// InSpace is a region of a space seen from the world, which is split into the
+/// same geohash cells.
+struct InSpace<'a, R: ?Sized>(&'a GeohashSpace, &'a R);
+
+impl<R: Region + ?Sized> Region for InSpace<'_, R> {
+    fn bounding_box(&self) -> Box {
+        self.0.world_box(&self.1.bounding_box())
+    }
+
+    fn contains_box(&self, b: &Box) -> bool {
+        self.1.contains_box(&self.0.space_box(b))
+    }
+
+    fn intersects_box(&self, b: &Box) -> bool {
+        self.1.intersects_box(&self.0.space_box(b))
+    }
+}
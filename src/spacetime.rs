@@ -0,0 +1,167 @@
+//! Spatio-temporal keys, which interleave the bits of a time with those of the
+//! latitude and longitude of a geohash into a single sortable key.
+//!
+//! A full key holds 32 bits of each of the longitude, latitude and time, 96
+//! bits in all, taking turns from the most significant bit. A key with bits of
+//! precision is the prefix of the full keys of every point and time in its
+//! cell, so its first two bits are those of the geohash.
+
+use crate::{
+    axis_bits3, curve_ranges, decode_extent, decode_range, encode_extent, encode_range, lng_spans,
+    low_mask, morton, Box,
+};
+
+/// MAX_BITS is the number of bits of a full key.
+pub const MAX_BITS: usize = 96;
+
+/// TimeRange is the span of times that keys can tell apart, from start up to
+/// end, in any unit such as seconds since the epoch. Times outside of it are
+/// clamped. The resolution of a key is the span split into as many parts as
+/// the time bits of its precision allow.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeRange {
+    pub start: f64,
+    pub end: f64,
+}
+
+impl TimeRange {
+    pub fn new(start: f64, end: f64) -> TimeRange {
+        TimeRange { start, end }
+    }
+
+    /// resolution returns the length of time of the cells of keys with bits of
+    /// precision.
+    pub fn resolution(&self, bits: usize) -> f64 {
+        error_with_precision(self, bits).2
+    }
+}
+
+/// SpaceTimeBox is a box in latitude/longitude space during the times from
+/// min_time to max_time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpaceTimeBox {
+    pub space: Box,
+    pub min_time: f64,
+    pub max_time: f64,
+}
+
+/// encode encodes the point (lat, lng) at the time to a full key.
+pub fn encode(lat: f64, lng: f64, time: f64, range: &TimeRange) -> u128 {
+    let lat_int = encode_range(lat, 90.0);
+    let lng_int = encode_range(lng, 180.0);
+    let time_int = encode_extent(time, range.start, range.end);
+    morton::encode3_128(time_int as u64, lat_int as u64, lng_int as u64, 32)
+}
+
+/// encode_with_precision encodes the point (lat, lng) at the time to a key
+/// with the specified number of bits (max MAX_BITS).
+pub fn encode_with_precision(
+    lat: f64,
+    lng: f64,
+    time: f64,
+    range: &TimeRange,
+    bits: usize,
+) -> u128 {
+    let bits = bits.min(MAX_BITS);
+    if bits == 0 {
+        return 0;
+    }
+    encode(lat, lng, time, range) >> (MAX_BITS - bits)
+}
+
+/// error_with_precision returns the latitude, longitude and time spans of the
+/// cells of keys with bits of precision.
+pub fn error_with_precision(range: &TimeRange, bits: usize) -> (f64, f64, f64) {
+    let (lng_bits, lat_bits, time_bits) = axis_bits3(bits.min(MAX_BITS));
+    (
+        180.0 / (lat_bits as f64).exp2(),
+        360.0 / (lng_bits as f64).exp2(),
+        (range.end - range.start) / (time_bits as f64).exp2(),
+    )
+}
+
+/// bounding_box returns the place and times of the key with bits of
+/// precision.
+pub fn bounding_box(key: u128, bits: usize, range: &TimeRange) -> SpaceTimeBox {
+    let bits = bits.min(MAX_BITS);
+    let (lat, lng, time) = cell_int_mins(key, bits);
+    let (lat_err, lng_err, time_err) = error_with_precision(range, bits);
+    let min_lat = decode_range(lat, 90.0);
+    let min_lng = decode_range(lng, 180.0);
+    let min_time = decode_extent(time, range.start, range.end);
+    SpaceTimeBox {
+        space: Box {
+            min_lat,
+            max_lat: min_lat + lat_err,
+            min_lng,
+            max_lng: min_lng + lng_err,
+        },
+        min_time,
+        max_time: min_time + time_err,
+    }
+}
+
+/// decode returns the point (lat, lng) and time at the center of the cell of
+/// the key with bits of precision.
+pub fn decode(key: u128, bits: usize, range: &TimeRange) -> (f64, f64, f64) {
+    let b = bounding_box(key, bits, range);
+    let (lat, lng) = b.space.center();
+    (lat, lng, (b.min_time + b.max_time) / 2.0)
+}
+
+/// box_to_ranges decomposes the place and times into a list of inclusive
+/// [first, last] ranges of full keys, like the box_to_ranges of integer
+/// geohashes. Every point inside the box during the times encodes to a key in
+/// one of the ranges, but the ranges may also cover keys outside of it. The
+/// cells are refined no deeper than max_bits of precision, and no further once
+/// that would yield more than max_ranges ranges. The ranges are sorted and
+/// disjoint. A box with min_lng greater than max_lng crosses the antimeridian.
+pub fn box_to_ranges(
+    b: &SpaceTimeBox,
+    range: &TimeRange,
+    max_bits: usize,
+    max_ranges: usize,
+) -> Vec<(u128, u128)> {
+    let s = &b.space;
+    if !(s.min_lat <= s.max_lat && b.min_time <= b.max_time) {
+        return Vec::new();
+    }
+    let lat = (encode_range(s.min_lat, 90.0), encode_range(s.max_lat, 90.0));
+    let time = (
+        encode_extent(b.min_time, range.start, range.end),
+        encode_extent(b.max_time, range.start, range.end),
+    );
+    let queries: Vec<[(u32, u32); 3]> = lng_spans(s)
+        .into_iter()
+        .map(|(min_lng, max_lng)| {
+            let lng = (encode_range(min_lng, 180.0), encode_range(max_lng, 180.0));
+            [lng, lat, time]
+        })
+        .collect();
+    curve_ranges(&queries, MAX_BITS, max_bits, max_ranges, cell_int_ranges)
+}
+
+/// cell_int_mins returns the smallest 32-bit latitude, longitude and time
+/// integers in the cell of the key with bits of precision.
+fn cell_int_mins(key: u128, bits: usize) -> (u32, u32, u32) {
+    let full = if bits == 0 {
+        0
+    } else {
+        key << (MAX_BITS - bits)
+    };
+    let (time, lat, lng) = morton::decode3_128(full, 32);
+    (lat as u32, lng as u32, time as u32)
+}
+
+/// cell_int_ranges returns the inclusive ranges of the 32-bit longitude,
+/// latitude and time integers in the cell of the key with bits of precision.
+fn cell_int_ranges(key: u128, bits: usize) -> [(u32, u32); 3] {
+    let (lat, lng, time) = cell_int_mins(key, bits);
+    let (lng_bits, lat_bits, time_bits) = axis_bits3(bits);
+    let range = |x: u32, bits: usize| (x, x | low_mask(32 - bits));
+    [
+        range(lng, lng_bits),
+        range(lat, lat_bits),
+        range(time, time_bits),
+    ]
+}
@@ -0,0 +1,89 @@
+use crate as geohash;
+use crate::alphabet::{self, Alphabet, Codec, Geohash36};
+
+#[test]
+fn built_in_alphabets() {
+    let hash = geohash::encode_with_precision(33.0, -117.0, 12);
+    assert!(geohash::encode_with_codec(33.0, -117.0, 12, &alphabet::BASE32) == hash);
+    let int = geohash::encode_int(33.0, -117.0);
+    let hex = geohash::encode_with_codec(33.0, -117.0, 16, &alphabet::BASE16);
+    assert!(hex == format!("{:016x}", int));
+    let quad = geohash::encode_with_codec(33.0, -117.0, 32, &alphabet::BASE4);
+    assert!(u64::from_str_radix(&quad, 4).unwrap() == int);
+    // Characters past the maximum are left out.
+    assert!(alphabet::BASE4.encode(33.0, -117.0, 40) == quad);
+    assert!(alphabet::BASE16.encode(33.0, -117.0, 0).is_empty());
+
+    for (codec, chars) in [
+        (alphabet::BASE32, 7),
+        (alphabet::BASE16, 9),
+        (alphabet::BASE4, 17),
+    ]
+    .iter()
+    {
+        let hash = codec.encode(33.0, -117.0, *chars);
+        let b = geohash::bounding_box_with_codec(&hash, codec).unwrap();
+        assert!(
+            b == geohash::bounding_box_int_with_precision(
+                codec.decode_int(&hash).unwrap(),
+                chars * codec.bits()
+            )
+        );
+        assert!(b.contains(33.0, -117.0));
+        let (lat, lng) = geohash::decode_with_codec(&hash, codec).unwrap();
+        assert!(b.contains(lat, lng));
+    }
+}
+
+#[test]
+fn validation() {
+    assert!(geohash::validate_with_codec("0123456789abcdef", &alphabet::BASE16).is_ok());
+    assert!(geohash::validate_with_codec("0123456789abcdef0", &alphabet::BASE16).is_err());
+    assert!(geohash::validate_with_codec("0124", &alphabet::BASE4).is_err());
+    assert!(alphabet::BASE32.decode_int("9mudqi").is_err());
+    assert!(alphabet::BASE32.value(b'z') == Some(31));
+    assert!(alphabet::BASE32.value(b'a').is_none());
+}
+
+#[test]
+fn custom_alphabets() {
+    let upper = Alphabet::new("0123456789BCDEFGHJKMNPQRSTUVWXYZ").unwrap();
+    assert!(upper.bits() == 5 && upper.symbols().len() == 32);
+    let hash = upper.encode(33.0, -117.0, 9);
+    assert!(hash == geohash::encode_with_precision(33.0, -117.0, 9).to_uppercase());
+    assert!(upper.bounding_box(&hash).unwrap() == geohash::bounding_box(&hash.to_lowercase()));
+    assert!(upper.validate("9mudq").is_err());
+
+    let binary = Alphabet::new("ns").unwrap();
+    assert!(binary.bits() == 1 && binary.max_chars() == 64);
+    // West, north, then the western half of the west.
+    assert!(binary.encode(33.0, -117.0, 3) == "nsn");
+
+    assert!(Alphabet::new("012").is_err());
+    assert!(Alphabet::new("0").is_err());
+    assert!(Alphabet::new("0120").is_err());
+    assert!(Alphabet::new("01é2").is_err());
+}
+
+#[test]
+fn geohash36() {
+    let codec = Geohash36;
+    assert!(codec.encode(51.504444, -0.086666, 10) == "bdrdC26BqH");
+    assert!(codec.encode(90.0, -180.0, 1) == "2");
+    assert!(codec.encode(-90.0, 180.0, 1) == "X");
+    let b = codec.bounding_box("bdrdC26BqH").unwrap();
+    assert!(b.contains(51.504444, -0.086666));
+    for _ in 0..1000 {
+        let lat = -90.0 + 180.0 * rand::random::<f64>();
+        let lng = -180.0 + 360.0 * rand::random::<f64>();
+        let chars = 1 + rand::random::<usize>() % 12;
+        let hash = geohash::encode_with_codec(lat, lng, chars, &codec);
+        assert!(hash.len() == chars);
+        let b = geohash::bounding_box_with_codec(&hash, &codec).unwrap();
+        assert!(b.contains(lat, lng));
+        let (lat, lng) = b.center();
+        assert!(codec.encode(lat, lng, chars) == hash);
+    }
+    assert!(codec.validate("bdrdc26BqH").is_err());
+    assert!(codec.bounding_box("0").is_err());
+}
@@ -0,0 +1,109 @@
+use crate as geohash;
+use crate::altitude::{self, AltitudeRange};
+
+/// From below the Dead Sea to above the cruising altitude of airliners.
+const RANGE: AltitudeRange = AltitudeRange {
+    min: -500.0,
+    max: 20_000.0,
+};
+
+fn random_point() -> (f64, f64, f64) {
+    (
+        -90.0 + 180.0 * rand::random::<f64>(),
+        -180.0 + 360.0 * rand::random::<f64>(),
+        RANGE.min + (RANGE.max - RANGE.min) * rand::random::<f64>(),
+    )
+}
+
+#[test]
+fn encode() {
+    let hash = altitude::encode(33.0, -117.0, 1000.0, &RANGE);
+    assert!(hash.len() == 12 && geohash::validate(&hash).is_ok());
+    assert!(altitude::encode_with_precision(33.0, -117.0, 1000.0, &RANGE, 5) == hash[..5]);
+    // The first two bits are those of the geohash.
+    let key = altitude::encode_int_with_precision(33.0, -117.0, 1000.0, &RANGE, 2);
+    assert!(key == geohash::encode_int_with_precision(33.0, -117.0, 2));
+    assert!(altitude::encode_int_with_precision(0.0, 0.0, RANGE.min, &RANGE, 3) & 1 == 0);
+    assert!(altitude::encode_int_with_precision(0.0, 0.0, RANGE.max, &RANGE, 3) & 1 == 1);
+    // Altitudes outside of the range are clamped.
+    assert!(
+        altitude::encode(33.0, -117.0, 50_000.0, &RANGE)
+            == altitude::encode(33.0, -117.0, RANGE.max, &RANGE)
+    );
+}
+
+#[test]
+fn bounding_box() {
+    for _ in 0..1000 {
+        let (lat, lng, alt) = random_point();
+        let chars = 1 + rand::random::<usize>() % 12;
+        let hash = altitude::encode_with_precision(lat, lng, alt, &RANGE, chars);
+        let b = altitude::bounding_box(&hash, &RANGE);
+        assert!(b.contains(lat, lng, alt));
+        let (clat, clng, calt) = altitude::decode(&hash, &RANGE);
+        assert!(altitude::encode_with_precision(clat, clng, calt, &RANGE, chars) == hash);
+
+        let bits = rand::random::<usize>() % (altitude::MAX_BITS + 1);
+        let key = altitude::encode_int_with_precision(lat, lng, alt, &RANGE, bits);
+        let b = altitude::bounding_box_int_with_precision(key, bits, &RANGE);
+        assert!(b.contains(lat, lng, alt));
+        let (clat, clng, calt) = altitude::decode_int_with_precision(key, bits, &RANGE);
+        assert!(altitude::encode_int_with_precision(clat, clng, calt, &RANGE, bits) == key);
+    }
+}
+
+#[test]
+fn neighbors() {
+    // A cell away from every edge has 26 neighbors that touch it.
+    let bits = 30;
+    let key = altitude::encode_int_with_precision(33.0, -117.0, 1000.0, &RANGE, bits);
+    let b = altitude::bounding_box_int_with_precision(key, bits, &RANGE);
+    let neighbors = altitude::neighbors_int_with_precision(key, bits);
+    assert!(neighbors.len() == 26);
+    for n in &neighbors {
+        let nb = altitude::bounding_box_int_with_precision(*n, bits, &RANGE);
+        assert!(nb.space.intersects(&b.space));
+        assert!(nb.min_alt <= b.max_alt && b.min_alt <= nb.max_alt);
+    }
+    // The lowest, first neighbor is below to the south-west.
+    let first = altitude::bounding_box_int_with_precision(neighbors[0], bits, &RANGE);
+    assert!(first.max_alt == b.min_alt && first.space.max_lat == b.space.min_lat);
+    assert!(first.space.max_lng == b.space.min_lng);
+
+    // At the ground and the north pole, cells below and above are left out.
+    let key = altitude::encode_int_with_precision(90.0, 0.0, RANGE.min, &RANGE, bits);
+    assert!(altitude::neighbors_int_with_precision(key, bits).len() == 11);
+    // Longitudes wrap around the antimeridian.
+    let key = altitude::encode_int_with_precision(0.0, 179.99, 0.0, &RANGE, bits);
+    let east = altitude::encode_int_with_precision(0.0, -179.99, 0.0, &RANGE, bits);
+    assert!(altitude::neighbors_int_with_precision(key, bits).contains(&east));
+    // Narrow cells have fewer distinct neighbors.
+    assert!(altitude::neighbors_int_with_precision(0, 1) == vec![1]);
+    assert!(altitude::neighbors_int_with_precision(0, 0).is_empty());
+
+    let hash = altitude::encode_with_precision(33.0, -117.0, 1000.0, &RANGE, 6);
+    let strings = altitude::neighbors(&hash);
+    assert!(strings.len() == 26 && strings.iter().all(|n| n.len() == 6));
+}
+
+#[test]
+fn parent_children() {
+    let hash = altitude::encode_with_precision(33.0, -117.0, 1000.0, &RANGE, 6);
+    assert!(altitude::parent(&hash) == hash[..5]);
+    assert!(altitude::parent("").is_empty());
+    let children = altitude::children(&hash);
+    assert!(children.len() == 32);
+    let child = altitude::encode_with_precision(33.0, -117.0, 1000.0, &RANGE, 7);
+    assert!(children.contains(&child));
+
+    let key = altitude::encode_int_with_precision(33.0, -117.0, 1000.0, &RANGE, 30);
+    let children = altitude::children_int(key);
+    let child = altitude::encode_int_with_precision(33.0, -117.0, 1000.0, &RANGE, 33);
+    assert!(children.contains(&child));
+    assert!(children.iter().all(|&c| altitude::parent_int(c, 33) == key));
+    let b = altitude::bounding_box_int_with_precision(key, 30, &RANGE);
+    let c = altitude::bounding_box_int_with_precision(children[0], 33, &RANGE);
+    assert!(c.space.max_lat - c.space.min_lat == (b.space.max_lat - b.space.min_lat) / 2.0);
+    assert!(c.max_alt - c.min_alt == (b.max_alt - b.min_alt) / 2.0);
+    assert!(altitude::parent_int(1, 1) == 0);
+}
@@ -0,0 +1,120 @@
+use crate as geohash;
+use crate::cellunion::{CellUnion, IntCellUnion};
+
+fn children(hash: &str) -> Vec<String> {
+    "0123456789bcdefghjkmnpqrstuvwxyz"
+        .chars()
+        .map(|c| format!("{}{}", hash, c))
+        .collect()
+}
+
+#[test]
+fn normalize() {
+    // Complete sibling groups merge into the parent.
+    let u = CellUnion::new(&children("9q8y"));
+    assert!(u.cells() == vec!["9q8y"]);
+
+    // Contained cells are dropped and the result is sorted.
+    let u = CellUnion::new(&["9q8yyz", "dr5r", "9q8y", "9q8yy", "dr5ru"]);
+    assert!(u.cells() == vec!["9q8y", "dr5r"]);
+
+    // Incomplete groups are kept as they are.
+    let mut hashes = children("9q8y");
+    hashes.pop();
+    let u = CellUnion::new(&hashes);
+    assert!(u.len() == 31);
+
+    // Merging cascades up through several levels.
+    let mut hashes = children("9q8");
+    hashes.retain(|h| h != "9q8y");
+    hashes.extend(children("9q8y"));
+    assert!(CellUnion::new(&hashes).cells() == vec!["9q8"]);
+
+    assert!(CellUnion::new::<&str>(&[]).is_empty());
+}
+
+#[test]
+fn int_normalize() {
+    let u = IntCellUnion::new(&[(0b100, 3), (0b101, 3), (0b11, 2)]);
+    assert!(u.cells() == [(0b1, 1)]);
+    let u = IntCellUnion::new(&[(0b1011, 4), (0b10, 2), (0b0, 1)]);
+    assert!(u.cells() == [(0b0, 1), (0b10, 2)]);
+    assert!(IntCellUnion::new(&[(0, 1), (1, 1)]).cells() == [(0, 0)]);
+}
+
+#[test]
+// Hashes longer than a 64-bit integer holds are cut to 64 bits.
+fn precision_limit() {
+    let u = CellUnion::new(&["9q8yyk9pkq6ts"]);
+    assert!(u.cells() == vec!["9q8yyk9pkq6t"]);
+    assert!(u.contains_cell("9q8yyk9pkq6t") && u.contains_cell("9q8yyk9pkq6tsb"));
+    assert!(!u.intersects_cell("9q8yyk9pkq6u0"));
+    let u = IntCellUnion::new(&[(0, 65)]);
+    assert!(u.cells() == [(0, 64)]);
+    assert!(u.contains_cell(0, 70) && !u.intersects_cell(1, 65));
+}
+
+#[test]
+// Hash bits above the precision are not part of the cell.
+fn high_bits() {
+    let u = IntCellUnion::new(&[(0xFF, 2)]);
+    assert!(u.cells() == [(0b11, 2)]);
+    assert!(u == IntCellUnion::new(&[(0b11, 2)]));
+    assert!(u.contains_cell(0xF, 2) && u.intersects_cell(0xFF, 1));
+    assert!(!u.contains_cell(0xFE, 2) && !u.intersects_cell(0xFE, 1));
+}
+
+#[test]
+fn set_operations() {
+    let a = CellUnion::new(&["9q8y", "9q9"]);
+    let b = CellUnion::new(&["9q8yy", "9q9p", "dr5r"]);
+
+    let u = a.union(&b);
+    assert!(u.cells() == vec!["9q8y", "9q9", "dr5r"]);
+
+    let i = a.intersection(&b);
+    assert!(i.cells() == vec!["9q8yy", "9q9p"]);
+
+    let d = a.difference(&b);
+    assert!(d.len() == 31 + 31);
+    assert!(!d.intersects_cell("9q8yy") && !d.intersects_cell("9q9p"));
+    assert!(d.contains_cell("9q8yz") && d.contains_cell("9q9r"));
+    assert!(d.union(&i) == a);
+
+    let ia = a.to_int();
+    let ib = b.to_int();
+    assert!(ia.intersection(&ib) == i.to_int());
+    assert!(ia.difference(&ib) == d.to_int());
+    assert!(ia.union(&ib) == u.to_int());
+}
+
+#[test]
+fn containment() {
+    let u = CellUnion::new(&["9q8y", "dr5r"]);
+    assert!(u.contains_cell("9q8y") && u.contains_cell("9q8yyk"));
+    assert!(!u.contains_cell("9q8") && !u.contains_cell("9q8z"));
+    assert!(u.intersects_cell("9q8") && u.intersects_cell("9q8yy"));
+    assert!(u.intersects_cell("") && !u.intersects_cell("9q9"));
+    assert!(!u.intersects_cell("dr5q") && !u.intersects_cell("9q8x"));
+
+    let (lat, lng) = geohash::decode_center("9q8yyk");
+    assert!(u.contains_point(lat, lng));
+    let (lat, lng) = geohash::decode_center("9q8z");
+    assert!(!u.contains_point(lat, lng));
+
+    let iu = u.to_int();
+    let hash = geohash::encode_int_with_precision(lat, lng, 22);
+    assert!(!iu.contains_cell(hash, 22) && !iu.intersects_cell(hash, 22));
+    assert!(iu.contains_cell(geohash::base32::decode(b"9q8yy"), 25));
+}
+
+#[test]
+fn area() {
+    let world = CellUnion::new(&[""]);
+    assert!(world.area() == 360.0 * 180.0);
+    let u = CellUnion::new(&children("9q8y"));
+    let b = geohash::bounding_box("9q8y");
+    let expected = (b.max_lat - b.min_lat) * (b.max_lng - b.min_lng);
+    assert!((u.area() - expected).abs() < 1e-12);
+    assert!(u.to_int().area() == u.area());
+}
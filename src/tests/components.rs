@@ -0,0 +1,117 @@
+use crate as geohash;
+use crate::components::{connected_components, connected_components_int, Connectivity};
+use crate::topology::{relation_int, CellRelation};
+
+#[test]
+fn connectivity() {
+    let ne = geohash::neighbor("9q8yy", geohash::NORTH_EAST);
+    let west = geohash::neighbor("9q8yy", geohash::WEST);
+    let hashes = ["9q8yy", ne.as_str(), "dr5ru", west.as_str()];
+    let groups = connected_components(&hashes[..2], Connectivity::Four);
+    assert!(groups.len() == 2);
+    let groups = connected_components(&hashes[..2], Connectivity::Eight);
+    assert!(groups.len() == 1 && groups[0].count == 2);
+
+    let groups = connected_components(&hashes, Connectivity::Four);
+    assert!(groups.len() == 3);
+    assert!(groups[0].cells == vec!["9q8yy".to_owned(), west.clone()]);
+    assert!(groups[1].cells == vec![ne.clone()]);
+    assert!(groups[2].cells == vec!["dr5ru".to_owned()]);
+    let (a, b) = (geohash::bounding_box("9q8yy"), geohash::bounding_box(&west));
+    assert!(
+        groups[0].bounding_box
+            == geohash::Box {
+                min_lng: b.min_lng,
+                ..a
+            }
+    );
+    assert!(connected_components::<&str>(&[], Connectivity::Four).is_empty());
+}
+
+#[test]
+fn antimeridian() {
+    let west = geohash::encode_with_precision(10.0, 179.99, 4);
+    let east = geohash::encode_with_precision(10.0, -179.99, 4);
+    let groups = connected_components(&[west.as_str(), east.as_str()], Connectivity::Four);
+    assert!(groups.len() == 1);
+    let b = groups[0].bounding_box;
+    assert!(b.min_lng == geohash::bounding_box(&west).min_lng);
+    assert!(b.max_lng == geohash::bounding_box(&east).max_lng);
+}
+
+#[test]
+fn mixed_precision() {
+    // A small cell touching a large one, and a cell inside another.
+    let north = geohash::neighbor("9q8y", geohash::NORTH);
+    let small = format!("{}0", north);
+    let hashes = ["9q8y", small.as_str(), "9q8yyy", "dr5"];
+    let groups = connected_components(&hashes, Connectivity::Four);
+    assert!(groups.len() == 2);
+    assert!(groups[0].count == 3);
+    let (a, b) = (geohash::bounding_box("9q8y"), geohash::bounding_box(&small));
+    assert!(
+        groups[0].bounding_box
+            == geohash::Box {
+                max_lat: b.max_lat,
+                ..a
+            }
+    );
+    // The corner of a large cell touching a small one.
+    let corner = format!("{}b", geohash::neighbor("9q8y", geohash::SOUTH_EAST));
+    let groups = connected_components(&["9q8y", corner.as_str()], Connectivity::Four);
+    assert!(groups.len() == 2);
+    let groups = connected_components(&["9q8y", corner.as_str()], Connectivity::Eight);
+    assert!(groups.len() == 1);
+}
+
+#[test]
+// The components must agree with a search that connects every pair of cells
+// directly.
+fn brute_force() {
+    for connectivity in [Connectivity::Four, Connectivity::Eight] {
+        for _ in 0..20 {
+            let cells: Vec<(u64, usize)> = (0..60)
+                .map(|_| {
+                    let bits = 4 + rand::random::<usize>() % 5;
+                    (rand::random::<u64>() >> (64 - bits), bits)
+                })
+                .collect();
+            let touch = |i: usize, j: usize| {
+                let (a, b) = (cells[i], cells[j]);
+                match relation_int(a.0, a.1, b.0, b.1) {
+                    CellRelation::Disjoint => false,
+                    CellRelation::SharesCorner => connectivity == Connectivity::Eight,
+                    _ => true,
+                }
+            };
+            let mut label = vec![usize::MAX; cells.len()];
+            let mut count = 0;
+            for start in 0..cells.len() {
+                if label[start] != usize::MAX {
+                    continue;
+                }
+                let mut stack = vec![start];
+                label[start] = count;
+                while let Some(i) = stack.pop() {
+                    for (j, l) in label.iter_mut().enumerate() {
+                        if *l == usize::MAX && touch(i, j) {
+                            *l = count;
+                            stack.push(j);
+                        }
+                    }
+                }
+                count += 1;
+            }
+
+            let groups = connected_components_int(&cells, connectivity);
+            assert!(groups.len() == count);
+            // Labels are assigned in order of the first cell as well.
+            for (g, group) in groups.iter().enumerate() {
+                assert!(group.count == group.cells.len());
+                for cell in &group.cells {
+                    assert!(cells.iter().zip(&label).any(|(c, &l)| c == cell && l == g));
+                }
+            }
+        }
+    }
+}
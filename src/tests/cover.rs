@@ -0,0 +1,167 @@
+use crate as geohash;
+use crate::cover::{cover, interior_cover, Circle, CoverOptions, Polygon, Region};
+
+fn random_box() -> geohash::Box {
+    let lat = -80.0 + 160.0 * rand::random::<f64>();
+    let lng = -170.0 + 340.0 * rand::random::<f64>();
+    geohash::Box {
+        min_lat: lat,
+        max_lat: lat + 5.0 * rand::random::<f64>(),
+        min_lng: lng,
+        max_lng: lng + 5.0 * rand::random::<f64>(),
+    }
+}
+
+fn covered(cells: &[String], lat: f64, lng: f64) -> bool {
+    let hash = geohash::encode(lat, lng);
+    cells.iter().any(|c| hash.starts_with(c.as_str()))
+}
+
+#[test]
+// Every point of the region must be covered.
+fn cover_box() {
+    for _ in 0..50 {
+        let b = random_box();
+        let cells = cover(&b, &CoverOptions::default());
+        assert!(!cells.is_empty() && cells.len() <= 8);
+        for _ in 0..100 {
+            let lat = b.min_lat + (b.max_lat - b.min_lat) * rand::random::<f64>();
+            let lng = b.min_lng + (b.max_lng - b.min_lng) * rand::random::<f64>();
+            assert!(covered(&cells, lat, lng), "{},{} not covered", lat, lng);
+        }
+    }
+}
+
+#[test]
+fn cover_options() {
+    let b = geohash::bounding_box("9q8yy");
+    let options = CoverOptions {
+        min_level: 1,
+        max_level: 12,
+        max_cells: 1,
+    };
+    assert!(cover(&b, &options).len() == 1);
+    let options = CoverOptions {
+        min_level: 6,
+        max_level: 6,
+        max_cells: 1,
+    };
+    let cells = cover(&b, &options);
+    assert!(cells.iter().all(|c| c.len() == 6));
+    assert!(cells.iter().filter(|c| c.starts_with("9q8yy")).count() == 32);
+}
+
+#[test]
+// Every cell of an interior covering must lie inside the region.
+fn interior_cover_box() {
+    for _ in 0..50 {
+        let b = random_box();
+        let options = CoverOptions {
+            min_level: 1,
+            max_level: 6,
+            max_cells: 50,
+        };
+        let cells = interior_cover(&b, &options);
+        assert!(cells.len() <= 50);
+        for cell in &cells {
+            assert!(cell.len() <= 6);
+            assert!(b.contains_box(&geohash::bounding_box(cell)));
+        }
+    }
+    // A cell is its own interior covering.
+    let b = geohash::bounding_box("9q8yy");
+    assert!(interior_cover(&b, &CoverOptions::default()) == vec!["9q8yy"]);
+    // A region smaller than any cell has none.
+    let options = CoverOptions {
+        min_level: 1,
+        max_level: 4,
+        max_cells: 8,
+    };
+    assert!(interior_cover(&b, &options).is_empty());
+}
+
+#[test]
+// Coverings of thin regions end quickly, and cells that do not all fit are
+// taken around the center of the region.
+fn interior_cover_limits() {
+    let line = geohash::Box {
+        min_lat: 10.0,
+        max_lat: 10.0,
+        min_lng: 0.0,
+        max_lng: 10.0,
+    };
+    assert!(interior_cover(&line, &CoverOptions::default()).is_empty());
+    let sliver = geohash::Box {
+        min_lat: 10.0,
+        max_lat: 10.000001,
+        min_lng: 0.0,
+        max_lng: 10.0,
+    };
+    let start = std::time::Instant::now();
+    interior_cover(&sliver, &CoverOptions::default());
+    assert!(start.elapsed().as_secs() < 5);
+
+    let b = geohash::bounding_box("9q8y");
+    let options = CoverOptions {
+        min_level: 5,
+        max_level: 5,
+        max_cells: 4,
+    };
+    let cells = interior_cover(&b, &options);
+    assert!(cells.len() == 4);
+    let (lat, lng) = b.center();
+    for cell in &cells {
+        assert!(geohash::bounding_box(cell).contains(lat, lng));
+    }
+}
+
+#[test]
+fn circle() {
+    let c = Circle::new(37.77, -122.42, 2000.0);
+    let options = CoverOptions {
+        min_level: 1,
+        max_level: 7,
+        max_cells: 32,
+    };
+    let cells = cover(&c, &options);
+    assert!(covered(&cells, 37.77, -122.42));
+    assert!(covered(&cells, 37.77 + 0.0179, -122.42));
+    assert!(covered(&cells, 37.77, -122.42 - 0.0226));
+    let interior = interior_cover(&c, &options);
+    assert!(!interior.is_empty());
+    for cell in &interior {
+        let b = geohash::bounding_box(cell);
+        assert!(c.contains_box(&b));
+        assert!(cells.iter().any(|c| cell.starts_with(c.as_str())));
+    }
+    assert!(c.intersects_box(&geohash::bounding_box(&geohash::encode(37.77, -122.42))));
+    assert!(!c.intersects_box(&geohash::bounding_box("9q9")));
+}
+
+#[test]
+fn polygon() {
+    // A square with a square hole.
+    let p = Polygon {
+        exterior: vec![(0.0, 0.0), (0.0, 10.0), (10.0, 10.0), (10.0, 0.0)],
+        holes: vec![vec![(4.0, 4.0), (4.0, 6.0), (6.0, 6.0), (6.0, 4.0)]],
+    };
+    assert!(p.contains(1.0, 1.0) && !p.contains(5.0, 5.0) && !p.contains(11.0, 5.0));
+    let options = CoverOptions {
+        min_level: 1,
+        max_level: 4,
+        max_cells: 200,
+    };
+    for cell in interior_cover(&p, &options) {
+        let b = geohash::bounding_box(&cell);
+        assert!(p.contains_box(&b));
+        assert!(!b.intersects(&geohash::Box {
+            min_lat: 4.5,
+            max_lat: 5.5,
+            min_lng: 4.5,
+            max_lng: 5.5,
+        }));
+    }
+    let cells = cover(&p, &options);
+    assert!(covered(&cells, 1.0, 1.0) && covered(&cells, 9.9, 9.9));
+    assert!(!covered(&cells, 5.0, 5.0) && !covered(&cells, -1.0, 5.0));
+}
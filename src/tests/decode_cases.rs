@@ -1,58 +1,58 @@
 use crate as geohash;
 
 pub struct Iter<'a> {
-	data: &'a str,
+    data: &'a str,
 }
 
 impl<'a> Iterator for Iter<'a> {
-	type Item = TestCase;
-	fn next(&mut self) -> Option<TestCase> {
-		match self.data.find("},\n") {
-			Some(index) => {
-				let line = &(&self.data[..index + 1]).trim();
-				let bpart = line.split(", Box{").nth(1).unwrap();
-				// println!("-----------\n{}\n----------", line);
-				let case = TestCase {
-					hash: line[2..].split("\",").nth(0).unwrap().to_owned(),
-					r#box: geohash::Box {
-						min_lat: bpart[..bpart.len() - 2]
-							.split(",")
-							.nth(0)
-							.map(|a| a.trim().parse().unwrap())
-							.unwrap(),
-						max_lat: bpart[..bpart.len() - 2]
-							.split(",")
-							.nth(1)
-							.map(|a| a.trim().parse().unwrap())
-							.unwrap(),
-						min_lng: bpart[..bpart.len() - 2]
-							.split(",")
-							.nth(2)
-							.map(|a| a.trim().parse().unwrap())
-							.unwrap(),
-						max_lng: bpart[..bpart.len() - 2]
-							.split(",")
-							.nth(3)
-							.map(|a| a.trim().parse().unwrap())
-							.unwrap(),
-					},
-				};
-				self.data = &self.data[index + 2..];
-				Some(case)
-			}
-			None => None,
-		}
-	}
+    type Item = TestCase;
+    fn next(&mut self) -> Option<TestCase> {
+        match self.data.find("},\n") {
+            Some(index) => {
+                let line = &self.data[..index + 1].trim();
+                let bpart = line.split(", Box{").nth(1).unwrap();
+                // println!("-----------\n{}\n----------", line);
+                let case = TestCase {
+                    hash: line[2..].split("\",").nth(0).unwrap().to_owned(),
+                    r#box: geohash::Box {
+                        min_lat: bpart[..bpart.len() - 2]
+                            .split(",")
+                            .nth(0)
+                            .map(|a| a.trim().parse().unwrap())
+                            .unwrap(),
+                        max_lat: bpart[..bpart.len() - 2]
+                            .split(",")
+                            .nth(1)
+                            .map(|a| a.trim().parse().unwrap())
+                            .unwrap(),
+                        min_lng: bpart[..bpart.len() - 2]
+                            .split(",")
+                            .nth(2)
+                            .map(|a| a.trim().parse().unwrap())
+                            .unwrap(),
+                        max_lng: bpart[..bpart.len() - 2]
+                            .split(",")
+                            .nth(3)
+                            .map(|a| a.trim().parse().unwrap())
+                            .unwrap(),
+                    },
+                };
+                self.data = &self.data[index + 2..];
+                Some(case)
+            }
+            None => None,
+        }
+    }
 }
 
 pub fn iter<'a>() -> Iter<'a> {
-	Iter { data: DATA }
+    Iter { data: DATA }
 }
 
 #[derive(Debug)]
 pub struct TestCase {
-	pub hash: String,
-	pub r#box: geohash::Box,
+    pub hash: String,
+    pub r#box: geohash::Box,
 }
 
 const DATA: &str = r#"
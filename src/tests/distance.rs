@@ -0,0 +1,253 @@
+use crate as geohash;
+use crate::distance::{self, DistanceModel};
+use crate::geodesic::WGS84;
+
+#[test]
+fn distance() {
+    let d = distance::distance(51.5007, 0.1246, 40.6892, 74.0445);
+    assert!((d - 5_574_840.0).abs() < 1000.0, "distance {}", d);
+    assert!(distance::distance(10.0, 20.0, 10.0, 20.0) == 0.0);
+    // Half way around the earth.
+    let d = distance::distance(0.0, 0.0, 0.0, 180.0);
+    assert!((d - std::f64::consts::PI * distance::EARTH_RADIUS).abs() < 1e-6);
+    // Across the antimeridian.
+    let d = distance::distance(0.0, 179.5, 0.0, -179.5);
+    assert!((d - distance::distance(0.0, 0.0, 0.0, 1.0)).abs() < 1e-6);
+}
+
+#[test]
+fn initial_bearing() {
+    let b = distance::initial_bearing(50.0664, -5.7147, 58.6439, -3.07);
+    assert!((b - 9.1198).abs() < 0.001, "bearing {}", b);
+    assert!(distance::initial_bearing(0.0, 0.0, 0.0, -10.0) == 270.0);
+    assert!(distance::initial_bearing(0.0, 0.0, 10.0, 0.0) == 0.0);
+}
+
+#[test]
+// Travelling to a destination and measuring back must agree.
+fn destination() {
+    for _ in 0..100 {
+        let lat = -80.0 + 160.0 * rand::random::<f64>();
+        let lng = -180.0 + 360.0 * rand::random::<f64>();
+        let bearing = 360.0 * rand::random::<f64>();
+        let meters = 1_000_000.0 * rand::random::<f64>();
+        let (lat2, lng2) = distance::destination(lat, lng, bearing, meters);
+        assert!((-180.0..180.0).contains(&lng2));
+        let d = distance::distance(lat, lng, lat2, lng2);
+        assert!((d - meters).abs() < 1e-3, "{} != {}", d, meters);
+        let b = distance::initial_bearing(lat, lng, lat2, lng2);
+        let diff = (b - bearing).abs();
+        assert!(diff.min(360.0 - diff) < 1e-6, "{} != {}", b, bearing);
+    }
+    let (lat, lng) = distance::destination(0.0, 179.9, 90.0, 22_239.0);
+    assert!(lat.abs() < 1e-9 && (lng + 179.9).abs() < 1e-3);
+}
+
+#[test]
+fn hashes() {
+    let d = distance::distance_between_hashes("9q8yy", "9q8yy");
+    assert!(d == 0.0);
+    let (lat1, lng1) = geohash::decode_center("9q8yy");
+    let (lat2, lng2) = geohash::decode_center("dr5ru");
+    let d = distance::distance_between_hashes("9q8yy", "dr5ru");
+    assert!(d == distance::distance(lat1, lng1, lat2, lng2));
+
+    let b = geohash::bounding_box("9q8yy");
+    let height = distance::distance(b.min_lat, b.min_lng, b.max_lat, b.min_lng);
+    let north = distance::move_hash("9q8yy", 0.0, height);
+    assert!(north == geohash::neighbor("9q8yy", geohash::NORTH));
+    let south = distance::move_hash("9q8yy", 180.0, height);
+    assert!(south == geohash::neighbor("9q8yy", geohash::SOUTH));
+    assert!(distance::move_hash("9q8yy", 45.0, 1.0) == "9q8yy");
+    assert!(distance::move_hash("", 90.0, 1000.0).is_empty());
+}
+
+fn sample_box(b: &geohash::Box, n: usize) -> Vec<(f64, f64)> {
+    let width = if b.min_lng <= b.max_lng {
+        b.max_lng - b.min_lng
+    } else {
+        b.max_lng + 360.0 - b.min_lng
+    };
+    let mut points = Vec::new();
+    for i in 0..=n {
+        for j in 0..=n {
+            let lat = b.min_lat + (b.max_lat - b.min_lat) * i as f64 / n as f64;
+            let lng = b.min_lng + width * j as f64 / n as f64;
+            points.push((lat, if lng > 180.0 { lng - 360.0 } else { lng }));
+        }
+    }
+    points
+}
+
+#[test]
+// The bounds must agree with the distances to a dense sample of the box.
+fn min_max_distance() {
+    for _ in 0..50 {
+        let lat = -90.0 + 180.0 * rand::random::<f64>();
+        let lng = -180.0 + 360.0 * rand::random::<f64>();
+        let min_lat = -90.0 + 170.0 * rand::random::<f64>();
+        let min_lng = -180.0 + 360.0 * rand::random::<f64>();
+        let b = geohash::Box {
+            min_lat,
+            max_lat: min_lat + 10.0 * rand::random::<f64>(),
+            min_lng,
+            max_lng: distance::normalize_lng(min_lng + 60.0 * rand::random::<f64>()),
+        };
+        let min = distance::min_distance(lat, lng, &b);
+        let max = distance::max_distance(lat, lng, &b);
+        let samples: Vec<f64> = sample_box(&b, 200)
+            .iter()
+            .map(|&(plat, plng)| distance::distance(lat, lng, plat, plng))
+            .collect();
+        let sample_min = samples.iter().copied().fold(f64::INFINITY, f64::min);
+        let sample_max = samples.iter().copied().fold(0.0, f64::max);
+        // The samples are at most a grid step away from the extremes.
+        let step = distance::distance(0.0, 0.0, 10.0 / 200.0, 60.0 / 200.0);
+        assert!(
+            min <= sample_min + 1e-6 && sample_min - min <= step,
+            "min distance from {},{} to {:?}: {} != {}",
+            lat,
+            lng,
+            b,
+            min,
+            sample_min
+        );
+        assert!(
+            max >= sample_max - 1e-6 && max - sample_max <= step,
+            "max distance from {},{} to {:?}: {} != {}",
+            lat,
+            lng,
+            b,
+            max,
+            sample_max
+        );
+    }
+}
+
+#[test]
+fn min_distance_edge() {
+    // The closest point is in the middle of the southern edge.
+    let b = geohash::Box {
+        min_lat: 10.0,
+        max_lat: 20.0,
+        min_lng: -50.0,
+        max_lng: 50.0,
+    };
+    let d = distance::min_distance(0.0, 0.0, &b);
+    assert!((d - distance::distance(0.0, 0.0, 10.0, 0.0)).abs() < 1e-6);
+    // The closest point is in the middle of the western edge, north of the
+    // point's latitude.
+    let b = geohash::Box {
+        min_lat: -10.0,
+        max_lat: 80.0,
+        min_lng: 60.0,
+        max_lng: 70.0,
+    };
+    let d = distance::min_distance(45.0, 0.0, &b);
+    assert!(d < distance::distance(45.0, 0.0, 45.0, 60.0));
+    assert!(distance::min_distance(15.0, 65.0, &b) == 0.0);
+    // The farthest point is the antipode.
+    let d = distance::max_distance(-15.0, -115.0, &b);
+    assert!((d - std::f64::consts::PI * distance::EARTH_RADIUS).abs() < 1e-6);
+}
+
+#[test]
+fn min_distance_antimeridian() {
+    let b = geohash::Box {
+        min_lat: -10.0,
+        max_lat: 10.0,
+        min_lng: 170.0,
+        max_lng: -170.0,
+    };
+    assert!(distance::min_distance(0.0, 180.0, &b) == 0.0);
+    assert!(distance::min_distance(0.0, -175.0, &b) == 0.0);
+    let d = distance::min_distance(0.0, -160.0, &b);
+    assert!((d - distance::distance(0.0, -160.0, 0.0, -170.0)).abs() < 1e-6);
+    let d = distance::max_distance(0.0, -160.0, &b);
+    assert!((d - distance::distance(0.0, -160.0, 10.0, 170.0)).abs() < 1e-6);
+    // A cell on the other side of the antimeridian.
+    let hash = geohash::encode_with_precision(0.0, -179.99, 5);
+    let d = distance::min_distance_to_hash(0.0, 179.99, &hash);
+    assert!(d < 2000.0, "{}", d);
+}
+
+#[test]
+fn min_max_distance_hashes() {
+    let (lat, lng) = geohash::decode_center("9q8yy");
+    assert!(distance::min_distance_to_hash(lat, lng, "9q8yy") == 0.0);
+    let b = geohash::bounding_box("9q8yy");
+    let d = distance::max_distance_to_hash(lat, lng, "9q8yy");
+    assert!((d - distance::distance(lat, lng, b.min_lat, b.min_lng)).abs() < 1.0);
+    let hash = geohash::base32::decode(b"9q8yy");
+    assert!(distance::max_distance_to_int_with_precision(lat, lng, hash, 25) == d);
+    let d = distance::min_distance_to_int_with_precision(lat, lng, hash >> 1, 24);
+    assert!(d == 0.0);
+    let d = distance::min_distance_to_hash(lat, lng, "9q8yz");
+    assert!(d > 0.0 && d <= distance::distance(lat, lng, b.min_lat, b.max_lng) + 1.0);
+}
+
+#[test]
+fn box_area() {
+    let world = geohash::bounding_box("");
+    let r = distance::EARTH_RADIUS;
+    let area = world.area_m2();
+    assert!((area - 4.0 * std::f64::consts::PI * r * r).abs() / area < 1e-12);
+    let wgs84 = DistanceModel::Ellipsoid(WGS84);
+    let area = world.area_m2_with_model(&wgs84);
+    assert!((area - 5.10065621724e14).abs() < 1e3, "{}", area);
+
+    // Crossing the antimeridian.
+    let b = geohash::Box {
+        min_lat: 0.0,
+        max_lat: 10.0,
+        min_lng: 175.0,
+        max_lng: -175.0,
+    };
+    let c = geohash::Box {
+        min_lat: 0.0,
+        max_lat: 10.0,
+        min_lng: 0.0,
+        max_lng: 10.0,
+    };
+    assert!((b.area_m2() - c.area_m2()).abs() < 1e-3);
+}
+
+#[test]
+// The cells of a level must add up to the whole world.
+fn cell_area() {
+    let wgs84 = DistanceModel::Ellipsoid(WGS84);
+    let chars = "0123456789bcdefghjkmnpqrstuvwxyz";
+    let (mut sphere, mut ellipsoid) = (0.0, 0.0);
+    for c in chars.chars() {
+        sphere += distance::cell_area_m2(&c.to_string());
+        ellipsoid += distance::cell_area_m2_with_model(&c.to_string(), &wgs84);
+    }
+    assert!((sphere - geohash::bounding_box("").area_m2()).abs() < 1e3);
+    assert!((ellipsoid - 5.10065621724e14).abs() < 1e3);
+    // Cells shrink towards the poles.
+    assert!(distance::cell_area_m2("s0") > distance::cell_area_m2("u0"));
+}
+
+#[test]
+fn cell_size() {
+    let r = distance::EARTH_RADIUS;
+    // "s" spans 0..45 degrees of latitude and 0..45 of longitude.
+    let height = distance::cell_height_m("s");
+    assert!((height - r * std::f64::consts::FRAC_PI_4).abs() < 1e-6);
+    let width = distance::cell_width_m("s");
+    let expected = r * 22.5f64.to_radians().cos() * std::f64::consts::FRAC_PI_4;
+    assert!((width - expected).abs() < 1e-6);
+
+    let wgs84 = DistanceModel::Ellipsoid(WGS84);
+    let height = distance::cell_height_m_with_model("s", &wgs84)
+        + distance::cell_height_m_with_model("u", &wgs84);
+    assert!((height - 10_001_965.729).abs() < 0.01, "{}", height);
+    // A degree of longitude on the equator is 111.319 km.
+    let width = wgs84.parallel_length(0.0, 1.0);
+    assert!((width - 111_319.491).abs() < 0.001);
+
+    // A 6 character cell is about 1.2 km by 0.6 km at the equator.
+    let hash = geohash::encode_with_precision(0.01, 0.01, 6);
+    assert!((distance::cell_width_m(&hash) - 1222.0).abs() < 1.0);
+    assert!((distance::cell_height_m(&hash) - 611.0).abs() < 1.0);
+}
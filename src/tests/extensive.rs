@@ -9,10 +9,11 @@ fn encode() {
         let hash = geohash::encode(c.lat, c.lng);
         assert!(
             c.hash == hash,
-            format!(
-                "incorrect encode string result for ({},{}): {} != {}",
-                c.lat, c.lng, c.hash, hash
-            )
+            "incorrect encode string result for ({},{}): {} != {}",
+            c.lat,
+            c.lng,
+            c.hash,
+            hash
         );
     }
 }
@@ -23,14 +24,12 @@ fn encode_int() {
         let hash_int = geohash::encode_int(c.lat, c.lng);
         assert!(
             c.hash_int == hash_int,
-            format!(
-                "incorrect encode integer result for ({},{}): {} != {} xor {}",
-                c.lat,
-                c.lng,
-                c.hash_int,
-                hash_int,
-                c.hash_int ^ hash_int
-            )
+            "incorrect encode integer result for ({},{}): {} != {} xor {}",
+            c.lat,
+            c.lng,
+            c.hash_int,
+            hash_int,
+            c.hash_int ^ hash_int
         );
     }
 }
@@ -43,10 +42,12 @@ fn prefix_property() {
             let pre = &c.hash[..chars];
             assert!(
                 pre == hash,
-                format!(
-                    "incorrect encode string result for ({},{}) at precision {}: {} != {}",
-                    c.lat, c.lng, chars, pre, hash
-                )
+                "incorrect encode string result for ({},{}) at precision {}: {} != {}",
+                c.lat,
+                c.lng,
+                chars,
+                pre,
+                hash
             );
         }
     }
@@ -59,7 +60,8 @@ fn bounding_box() {
         let b = geohash::bounding_box(&c.hash);
         assert!(
             b.contains(c.lat, c.lng),
-            format!("incorrect bounding box for {}", c.hash)
+            "incorrect bounding box for {}",
+            c.hash
         );
     }
 }
@@ -71,7 +73,8 @@ fn bounding_box_int() {
         let b = geohash::bounding_box_int(c.hash_int);
         assert!(
             b.contains(c.lat, c.lng),
-            format!("incorrect bounding box for {}", c.hash_int)
+            "incorrect bounding box for {}",
+            c.hash_int
         );
     }
 }
@@ -83,11 +86,13 @@ fn decode_int() {
         let (lat, lng) = geohash::decode_int(c.hash_int);
         assert!(
             (lat - c.lat).abs() <= 0.0000001,
-            format!("large error in decoded latitude for {}", c.hash_int)
+            "large error in decoded latitude for {}",
+            c.hash_int
         );
         assert!(
             (lng - c.lng).abs() <= 0.0000001,
-            format!("large error in decoded longitude for {}", c.hash_int)
+            "large error in decoded longitude for {}",
+            c.hash_int
         );
     }
 }
@@ -99,10 +104,11 @@ fn decode() {
         let (lat, lng) = geohash::decode(&c.hash);
         assert!(
             c.r#box.contains(lat, lng),
-            format!(
-                "hash {} decoded to {},{} should lie in {:?}",
-                c.hash, lat, lng, c.r#box
-            )
+            "hash {} decoded to {},{} should lie in {:?}",
+            c.hash,
+            lat,
+            lng,
+            c.r#box
         );
     }
 }
@@ -114,10 +120,11 @@ fn decode_center() {
         let (lat, lng) = geohash::decode_center(&c.hash);
         assert!(
             c.r#box.contains(lat, lng),
-            format!(
-                "hash {} decoded to {},{} should lie in {:?}",
-                c.hash, lat, lng, c.r#box
-            )
+            "hash {} decoded to {},{} should lie in {:?}",
+            c.hash,
+            lat,
+            lng,
+            c.r#box
         );
     }
 }
@@ -131,7 +138,9 @@ fn decode_then_encode() {
         let rehashed = geohash::encode_with_precision(lat, lng, precision);
         assert!(
             c.hash == rehashed,
-            format!("hash {} decoded and re-encoded to {}", c.hash, rehashed)
+            "hash {} decoded and re-encoded to {}",
+            c.hash,
+            rehashed
         );
     }
 }
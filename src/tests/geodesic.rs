@@ -0,0 +1,130 @@
+use crate::cover::{cover, interior_cover, Circle, CoverOptions, Region};
+use crate::distance::{self, DistanceModel};
+use crate::geodesic::{self, Ellipsoid, GRS80, WGS84};
+
+#[test]
+// Vincenty's own example, Flinders Peak to Buninyong.
+fn inverse() {
+    let g = geodesic::inverse(-37.95103342, 144.42486789, -37.65282114, 143.92649554);
+    assert!((g.distance - 54972.271).abs() < 0.001, "{}", g.distance);
+    assert!((g.azimuth1 - 306.868158).abs() < 1e-5, "{}", g.azimuth1);
+    assert!((g.azimuth2 - 307.173630).abs() < 1e-3, "{}", g.azimuth2);
+
+    // A degree of longitude along the equator.
+    let d = geodesic::distance(0.0, 0.0, 0.0, 1.0);
+    assert!((d - 111_319.491).abs() < 0.001, "{}", d);
+    assert!(geodesic::distance(0.0, 179.5, 0.0, -179.5) == geodesic::distance(0.0, 0.0, 0.0, 1.0));
+    // A quarter meridian.
+    let d = geodesic::distance(0.0, 0.0, 90.0, 0.0);
+    assert!((d - 10_001_965.729).abs() < 0.01, "{}", d);
+    assert!(geodesic::distance(12.0, 34.0, 12.0, 34.0) == 0.0);
+}
+
+#[test]
+// Vincenty's method does not converge for nearly antipodal points, which are
+// solved separately. Karney's example from "Algorithms for geodesics".
+fn antipodal() {
+    let g = geodesic::inverse(-30.0, 0.0, 29.9, 179.8);
+    assert!(
+        (g.distance - 19_989_832.828).abs() < 0.001,
+        "{}",
+        g.distance
+    );
+    assert!((g.azimuth1 - 161.890525).abs() < 1e-5, "{}", g.azimuth1);
+    assert!((g.azimuth2 - 18.090737).abs() < 1e-5, "{}", g.azimuth2);
+    // Half a meridian, through either pole.
+    let d = geodesic::distance(0.0, 0.0, 0.0, 180.0);
+    assert!((d - 2.0 * 10_001_965.729).abs() < 0.01, "{}", d);
+
+    // Travelling the solution must reach the second point.
+    for _ in 0..100 {
+        let lat = -80.0 + 160.0 * rand::random::<f64>();
+        let lng = -180.0 + 360.0 * rand::random::<f64>();
+        let lat2 = -lat + rand::random::<f64>() - 0.5;
+        let lng2 = lng + 179.0 + 2.0 * rand::random::<f64>();
+        let g = geodesic::inverse(lat, lng, lat2, lng2);
+        assert!(g.distance > 19_800_000.0 && g.distance < 20_003_931.459);
+        let d = geodesic::direct(lat, lng, g.azimuth1, g.distance);
+        let dlng = distance::normalize_lng(d.lng - lng2);
+        assert!(
+            (d.lat - lat2).abs() < 1e-8 && dlng.abs() < 1e-8,
+            "{:?}",
+            (lat, lng, lat2, lng2)
+        );
+        let diff = (d.azimuth - g.azimuth2).abs();
+        assert!(diff.min(360.0 - diff) < 1e-8);
+    }
+}
+
+#[test]
+// Travelling to a destination and measuring back must agree.
+fn direct() {
+    for _ in 0..100 {
+        let lat = -80.0 + 160.0 * rand::random::<f64>();
+        let lng = -180.0 + 360.0 * rand::random::<f64>();
+        let azimuth = 360.0 * rand::random::<f64>();
+        let meters = 5_000_000.0 * rand::random::<f64>();
+        let d = geodesic::direct(lat, lng, azimuth, meters);
+        assert!((-180.0..180.0).contains(&d.lng));
+        let g = geodesic::inverse(lat, lng, d.lat, d.lng);
+        assert!(
+            (g.distance - meters).abs() < 1e-3,
+            "{} != {}",
+            g.distance,
+            meters
+        );
+        let diff = (g.azimuth1 - azimuth).abs();
+        assert!(diff.min(360.0 - diff) < 1e-6);
+        let diff = (g.azimuth2 - d.azimuth).abs();
+        assert!(diff.min(360.0 - diff) < 1e-6);
+    }
+}
+
+#[test]
+fn ellipsoids() {
+    assert!(Ellipsoid::default() == WGS84);
+    let sphere = Ellipsoid::new(distance::EARTH_RADIUS, 0.0);
+    let d = sphere.distance(10.0, 20.0, 30.0, 40.0);
+    assert!((d - distance::distance(10.0, 20.0, 30.0, 40.0)).abs() < 1e-6);
+    let d1 = WGS84.distance(10.0, 20.0, 30.0, 40.0);
+    let d2 = GRS80.distance(10.0, 20.0, 30.0, 40.0);
+    assert!(d1 != d2 && (d1 - d2).abs() < 0.001);
+}
+
+#[test]
+fn distance_model() {
+    let sphere = DistanceModel::default();
+    let wgs84 = DistanceModel::Ellipsoid(WGS84);
+    let d = sphere.distance(10.0, 20.0, 30.0, 40.0);
+    assert!(d == distance::distance(10.0, 20.0, 30.0, 40.0));
+    let d = wgs84.distance(10.0, 20.0, 30.0, 40.0);
+    assert!(d == geodesic::distance(10.0, 20.0, 30.0, 40.0));
+    let d = DistanceModel::Sphere(1.0).distance(0.0, 0.0, 0.0, 180.0);
+    assert!((d - std::f64::consts::PI).abs() < 1e-12);
+}
+
+#[test]
+// Circle coverings with an ellipsoid must respect geodesic distances.
+fn circle() {
+    let c = Circle::with_model(60.0, 10.0, 5000.0, DistanceModel::Ellipsoid(WGS84));
+    let options = CoverOptions {
+        min_level: 1,
+        max_level: 7,
+        max_cells: 64,
+    };
+    let b = c.bounding_box();
+    for &azimuth in &[0.0, 45.0, 90.0, 180.0, 270.0] {
+        let d = geodesic::direct(60.0, 10.0, azimuth, 4999.0);
+        assert!(b.contains(d.lat, d.lng));
+        let hash = crate::encode(d.lat, d.lng);
+        assert!(cover(&c, &options)
+            .iter()
+            .any(|cell| hash.starts_with(cell.as_str())));
+    }
+    for cell in interior_cover(&c, &options) {
+        let b = crate::bounding_box(&cell);
+        for &(lat, lng) in &[(b.min_lat, b.min_lng), (b.max_lat, b.max_lng)] {
+            assert!(geodesic::distance(60.0, 10.0, lat, lng) <= 5000.0);
+        }
+    }
+}
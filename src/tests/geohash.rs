@@ -8,19 +8,17 @@ fn random_point() -> (f64, f64) {
     )
 }
 
-
 fn random_box() -> geohash::Box {
     let (lat1, lng1) = random_point();
     let (lat2, lng2) = random_point();
-    return geohash::Box {
+    geohash::Box {
         min_lat: lat1.min(lat2),
         max_lat: lat1.max(lat2),
         min_lng: lng1.min(lng2),
         max_lng: lng1.max(lng2),
-    };
+    }
 }
 
-
 #[test]
 fn interleaving() {
     let cases = [
@@ -50,6 +48,18 @@ fn base32_decode() {
     assert!(0xdfe082 == x, "incorrect base64 decoding");
 }
 
+#[test]
+// Only the characters of the base32 alphabet are valid, and the others decode
+// to zero.
+fn base32_validate() {
+    assert!(geohash::validate("9mudq7").is_ok());
+    assert!(geohash::validate("0").is_ok());
+    assert!(geohash::validate("a").is_err());
+    assert!(geohash::validate("9mudqi").is_err());
+    assert!(geohash::validate("é").is_err());
+    assert!(geohash::base32::decode(b"o") == 0);
+}
+
 #[test]
 fn base32_encode() {
     let enc = geohash::base32::encode(0xdfe082);
@@ -61,6 +71,15 @@ fn box_round() {
     let b = random_box();
     let (lat, lng) = b.round();
     assert!(b.contains(lat, lng));
+    // Rounding the edge of a cell to its own decimal places must not step out.
+    let b = geohash::Box {
+        min_lat: -26.71875,
+        max_lat: -26.71870708465576,
+        min_lng: 155.31861305236816,
+        max_lng: 155.3186559677124,
+    };
+    let (lat, lng) = b.round();
+    assert!(b.contains(lat, lng));
 }
 #[test]
 fn box_center() {
@@ -95,7 +114,10 @@ fn box_contains() {
     for c in &cases {
         assert!(
             c.2 == b.contains(c.0, c.1),
-            format!("contains {},{} should be {}", c.0, c.1, c.2)
+            "contains {},{} should be {}",
+            c.0,
+            c.1,
+            c.2
         );
     }
 }
@@ -116,12 +138,12 @@ fn leading_zero() {
 fn neighbors_test() {
     for c in neighbors_test_cases::iter() {
         let neighbors = geohash::neighbors(&c.hash_str);
-        for i in 0..neighbors.len() {
-            let neighbor = &neighbors[i];
-            let expected = &c.hash_str_neighbors[i];
+        for (neighbor, expected) in neighbors.iter().zip(&c.hash_str_neighbors) {
             assert!(
                 neighbor == expected,
-                format!("actual: {} \n expected: {}\n", neighbor, expected)
+                "actual: {} \n expected: {}\n",
+                neighbor,
+                expected
             );
         }
     }
@@ -145,15 +167,12 @@ fn neighbors_int_test() {
 
     for c in &cases {
         let neighbors = geohash::neighbors_int(c.0);
-        for i in 0..neighbors.len() {
-            let neighbor = &neighbors[i];
-            let expected = &c.1[i];
+        for (neighbor, expected) in neighbors.iter().zip(&c.1) {
             assert!(
                 neighbor == expected,
-                format!(
-                    "neighbor: {} does not match expected: {}",
-                    neighbor, expected
-                )
+                "neighbor: {} does not match expected: {}",
+                neighbor,
+                expected
             );
         }
     }
@@ -162,12 +181,12 @@ fn neighbors_int_test() {
 fn neighbor_int_with_precision_test() {
     for c in neighbors_test_cases::iter() {
         let neighbors = geohash::neighbors_int_with_precision(c.hash_int, c.hash_int_bit_depth);
-        for i in 0..neighbors.len() {
-            let neighbor = &neighbors[i];
-            let expected = &c.hash_int_neighbors[i];
+        for (neighbor, expected) in neighbors.iter().zip(&c.hash_int_neighbors) {
             assert!(
                 neighbor == expected,
-                format!("actual: {} \n expected: {}\n", neighbor, expected)
+                "actual: {} \n expected: {}\n",
+                neighbor,
+                expected
             );
         }
     }
@@ -0,0 +1,123 @@
+use crate as geohash;
+use crate::hilbert;
+
+fn random_point() -> (f64, f64) {
+    (
+        -90.0 + 180.0 * rand::random::<f64>(),
+        -180.0 + 360.0 * rand::random::<f64>(),
+    )
+}
+
+fn contains(b: &geohash::Box, lat: f64, lng: f64) -> bool {
+    b.min_lat <= lat && lat <= b.max_lat && b.min_lng <= lng && lng <= b.max_lng
+}
+
+fn area(b: &geohash::Box) -> f64 {
+    (b.max_lat - b.min_lat) * (b.max_lng - b.min_lng)
+}
+
+#[test]
+fn encode() {
+    // The curve starts in the south-west and ends in the south-east.
+    assert!(hilbert::encode_int_with_precision(-45.0, -90.0, 2) == 0);
+    assert!(hilbert::encode_int_with_precision(45.0, -90.0, 2) == 1);
+    assert!(hilbert::encode_int_with_precision(45.0, 90.0, 2) == 2);
+    assert!(hilbert::encode_int_with_precision(-45.0, 90.0, 2) == 3);
+    assert!(hilbert::encode_int(-90.0, -180.0) == 0);
+}
+
+#[test]
+fn cells() {
+    for _ in 0..1000 {
+        let (lat, lng) = random_point();
+        let bits = 1 + rand::random::<usize>() % 64;
+        let hash = hilbert::encode_int_with_precision(lat, lng, bits);
+        let b = hilbert::bounding_box_int_with_precision(hash, bits);
+        assert!(contains(&b, lat, lng));
+        let g = geohash::bounding_box_int_with_precision(
+            geohash::encode_int_with_precision(lat, lng, bits),
+            bits,
+        );
+        assert!((area(&b) - area(&g)).abs() <= 1e-9 * area(&g));
+        if bits & 1 == 0 {
+            assert!(b == g);
+        }
+        let (dlat, dlng) = hilbert::decode_int_with_precision(hash, bits);
+        assert!(contains(&b, dlat, dlng));
+    }
+    let b = hilbert::bounding_box_int_with_precision(0, 0);
+    assert!(b.min_lat == -90.0 && b.max_lat == 90.0 && b.min_lng == -180.0 && b.max_lng == 180.0);
+}
+
+#[test]
+// Consecutive cells along the curve share an edge.
+fn locality() {
+    for _ in 0..1000 {
+        let bits = 2 * (1 + rand::random::<usize>() % 16);
+        let (lat, lng) = random_point();
+        let hash = hilbert::encode_int_with_precision(lat, lng, bits);
+        if hash == (1 << bits) - 1 {
+            continue;
+        }
+        let a = hilbert::bounding_box_int_with_precision(hash, bits);
+        let b = hilbert::bounding_box_int_with_precision(hash + 1, bits);
+        let lat_edge = a.min_lat == b.min_lat && (a.max_lng == b.min_lng || b.max_lng == a.min_lng);
+        let lng_edge = a.min_lng == b.min_lng && (a.max_lat == b.min_lat || b.max_lat == a.min_lat);
+        assert!(lat_edge || lng_edge);
+    }
+}
+
+#[test]
+fn neighbors() {
+    let hash = hilbert::encode_int_with_precision(10.0, 10.0, 20);
+    let b = hilbert::bounding_box_int_with_precision(hash, 20);
+    let (lat, lng) = b.center();
+    let n = hilbert::neighbors_int_with_precision(hash, 20);
+    let lat_delta = b.max_lat - b.min_lat;
+    let lng_delta = b.max_lng - b.min_lng;
+    assert!(n[geohash::NORTH] == hilbert::encode_int_with_precision(lat + lat_delta, lng, 20));
+    assert!(
+        n[geohash::SOUTH_WEST]
+            == hilbert::encode_int_with_precision(lat - lat_delta, lng - lng_delta, 20)
+    );
+    assert!(hilbert::neighbor_int_with_precision(hash, 20, geohash::EAST) == n[geohash::EAST]);
+    assert!(
+        hilbert::neighbors_int(hilbert::encode_int(10.0, 10.0))[geohash::WEST]
+            != hilbert::encode_int(10.0, 10.0)
+    );
+}
+
+#[test]
+// Every point inside the box must fall into one of the ranges.
+fn box_to_ranges() {
+    for _ in 0..100 {
+        let (lat, lng) = random_point();
+        let b = geohash::Box {
+            min_lat: lat,
+            max_lat: (lat + 10.0 * rand::random::<f64>()).min(90.0),
+            min_lng: lng,
+            max_lng: (lng + 10.0 * rand::random::<f64>()).min(180.0),
+        };
+        let max_ranges = 1 + rand::random::<usize>() % 16;
+        let ranges = hilbert::box_to_ranges(&b, 40, max_ranges);
+        assert!(!ranges.is_empty() && ranges.len() <= max_ranges);
+        for w in ranges.windows(2) {
+            assert!(w[0].0 <= w[0].1 && w[0].1 + 1 < w[1].0);
+        }
+        for _ in 0..100 {
+            let lat = b.min_lat + (b.max_lat - b.min_lat) * rand::random::<f64>();
+            let lng = b.min_lng + (b.max_lng - b.min_lng) * rand::random::<f64>();
+            let key = hilbert::encode_int(lat, lng);
+            assert!(ranges
+                .iter()
+                .any(|&(first, last)| first <= key && key <= last));
+        }
+    }
+    let world = geohash::Box {
+        min_lat: -90.0,
+        max_lat: 90.0,
+        min_lng: -180.0,
+        max_lng: 180.0,
+    };
+    assert!(hilbert::box_to_ranges(&world, 64, 8) == vec![(0, u64::MAX)]);
+}
@@ -0,0 +1,80 @@
+use crate as geohash;
+use crate::maidenhead;
+
+#[test]
+fn encode() {
+    // Newington, Connecticut.
+    assert!(maidenhead::encode(41.714775, -72.727260, 3) == "FN31pr");
+    assert!(maidenhead::encode(41.714775, -72.727260, 1) == "FN");
+    assert!(maidenhead::encode(41.714775, -72.727260, 4) == "FN31pr21");
+    assert!(maidenhead::encode(-90.0, -180.0, 2) == "AA00");
+    assert!(maidenhead::encode(90.0, 180.0, 3) == "RR99xx");
+}
+
+#[test]
+fn decode() {
+    let b = maidenhead::decode("FN31pr").unwrap();
+    assert!((b.min_lng + 72.75).abs() < 1e-9 && (b.max_lng + 72.75 - 5.0 / 60.0).abs() < 1e-9);
+    assert!((b.min_lat - 41.708_333_333).abs() < 1e-6 && (b.max_lat - 41.75).abs() < 1e-9);
+    assert!(maidenhead::decode("fn31PR") == Ok(b));
+    assert!(
+        maidenhead::decode("JJ")
+            == Ok(geohash::Box {
+                min_lat: 0.0,
+                max_lat: 10.0,
+                min_lng: 0.0,
+                max_lng: 20.0,
+            })
+    );
+    for _ in 0..100 {
+        let lat = -90.0 + 180.0 * rand::random::<f64>();
+        let lng = -180.0 + 360.0 * rand::random::<f64>();
+        let pairs = 1 + rand::random::<usize>() % maidenhead::MAX_PAIRS;
+        let locator = maidenhead::encode(lat, lng, pairs);
+        let b = maidenhead::decode(&locator).unwrap();
+        let eps = 1e-9;
+        assert!(b.min_lat - eps <= lat && lat <= b.max_lat + eps);
+        assert!(b.min_lng - eps <= lng && lng <= b.max_lng + eps);
+    }
+}
+
+#[test]
+fn validate() {
+    assert!(maidenhead::validate("FN31pr") == Ok(true));
+    assert!(maidenhead::validate("").is_err());
+    assert!(maidenhead::validate("FN3").is_err());
+    assert!(maidenhead::validate("SN").is_err());
+    assert!(maidenhead::validate("FNa1").is_err());
+    assert!(maidenhead::validate("FN31yr").is_err());
+    assert!(maidenhead::validate("FN31pr21ab00xx99aa00").is_ok());
+    assert!(maidenhead::validate("FN31pr21ab00xx99aa0011").is_err());
+}
+
+#[test]
+fn hashes() {
+    let hash = maidenhead::enclosing_hash("FN31pr").unwrap();
+    let b = maidenhead::decode("FN31pr").unwrap();
+    assert!(geohash::bounding_box(&hash).contains_box(&b));
+    // No longer geohash contains it.
+    for c in geohash::base32::BASE32_ENCODING.iter() {
+        let child = format!("{}{}", hash, *c as char);
+        assert!(!geohash::bounding_box(&child).contains_box(&b));
+    }
+    assert!(maidenhead::enclosing_hash("JJ") == Ok("s".to_owned()));
+    // The field LJ straddles the cells "s" and "t", which only the whole
+    // world contains.
+    assert!(maidenhead::enclosing_hash("LJ") == Ok(String::new()));
+
+    assert!(maidenhead::cover_hashes("LJ", 1).unwrap() == vec!["s", "t"]);
+    let hashes = maidenhead::cover_hashes("JJ", 2).unwrap();
+    assert!(hashes == vec!["s0", "s2", "s1", "s3"]);
+    let hashes = maidenhead::cover_hashes("FN31pr", 6).unwrap();
+    for hash in &hashes {
+        let c = geohash::bounding_box(hash);
+        assert!(c.intersects(&b) && !(c.max_lat == b.min_lat || c.min_lat == b.max_lat));
+    }
+    let (lat, lng) = b.center();
+    assert!(hashes.contains(&geohash::encode_with_precision(lat, lng, 6)));
+    assert!(maidenhead::cover_hashes("ZZ", 6).is_err());
+    assert!(maidenhead::cover_hashes("FN31pr", 13).is_err());
+}
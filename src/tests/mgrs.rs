@@ -0,0 +1,125 @@
+use crate as geohash;
+use crate::mgrs;
+use crate::utm::Hemisphere;
+
+#[test]
+fn encode() {
+    assert!(mgrs::encode(48.8583, 2.2945, 5) == "31UDQ4825111943");
+    assert!(mgrs::encode(48.8583, 2.2945, 2) == "31UDQ4811");
+    assert!(mgrs::encode(48.8583, 2.2945, 0) == "31UDQ");
+    assert!(mgrs::encode(0.0, 0.0, 5) == "31NAA6602100000");
+    assert!(mgrs::encode(-33.8568, 151.2153, 3) == "56HLH349522");
+    assert!(mgrs::encode(90.0, 0.0, 5) == "ZAH0000000000");
+    assert!(mgrs::encode(-90.0, 0.0, 5) == "BAN0000000000");
+    assert!(mgrs::encode(85.0, -10.0, 0).starts_with('Y'));
+}
+
+#[test]
+fn decode() {
+    let (utm, size) = mgrs::decode_utm("31U DQ 48251 11943").unwrap();
+    assert!(utm.zone == 31 && utm.hemisphere == Hemisphere::North && size == 1.0);
+    assert!(utm.easting == 448_251.0 && utm.northing == 5_411_943.0);
+    let (utm, size) = mgrs::decode_utm("56hlh349522").unwrap();
+    assert!(utm.hemisphere == Hemisphere::South && size == 100.0);
+    assert!(utm.easting == 334_900.0 && utm.northing == 6_252_200.0);
+    let (utm, _) = mgrs::decode_utm("BAN").unwrap();
+    assert!(utm.zone == 0 && utm.easting == 2_000_000.0 && utm.northing == 2_000_000.0);
+
+    let b = mgrs::decode("31UDQ4825111943").unwrap();
+    assert!(b.contains(48.8583, 2.2945));
+    assert!(b.max_lat - b.min_lat < 2e-5);
+    // A square holding the north pole.
+    let b = mgrs::decode("ZAH").unwrap();
+    assert!(b.max_lat == 90.0 && b.min_lng == -180.0 && b.min_lat > 88.5);
+
+    for bad in [
+        "",
+        "31",
+        "61UDQ",
+        "31IDQ",
+        "31UDI",
+        "31UDQ123",
+        "31UDQ12a4",
+        "CAH",
+        "31UDQ123456789012",
+    ] {
+        assert!(mgrs::decode(bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+// Encoding and decoding must give a square that holds the point.
+fn round_trip() {
+    for _ in 0..1000 {
+        let lat = -90.0 + 180.0 * rand::random::<f64>();
+        let lng = -180.0 + 360.0 * rand::random::<f64>();
+        let digits = rand::random::<usize>() % (mgrs::MAX_DIGITS + 1);
+        let reference = mgrs::encode(lat, lng, digits);
+        let (utm, size) = mgrs::decode_utm(&reference).unwrap();
+        let point = geohash::utm::Utm::from_lat_lng_in_zone(lat, lng, utm.zone.max(1));
+        let point = if utm.zone == 0 {
+            geohash::utm::Utm::from_lat_lng(lat, lng)
+        } else {
+            point
+        };
+        assert!(
+            utm.easting <= point.easting + 1e-6 && point.easting < utm.easting + size + 1e-6,
+            "{} {} {} {:?} {:?}",
+            lat,
+            lng,
+            reference,
+            utm,
+            point
+        );
+        assert!(
+            utm.northing <= point.northing + 1e-6 && point.northing < utm.northing + size + 1e-6,
+            "{} {} {} {:?} {:?}",
+            lat,
+            lng,
+            reference,
+            utm,
+            point
+        );
+        if digits >= 2 {
+            let b = mgrs::decode(&reference).unwrap();
+            let eps = 1e-7;
+            let inside_lng = if b.min_lng <= b.max_lng {
+                b.min_lng - eps <= lng && lng <= b.max_lng + eps
+            } else {
+                lng >= b.min_lng - eps || lng <= b.max_lng + eps
+            };
+            assert!(
+                b.min_lat - eps <= lat && lat <= b.max_lat + eps && inside_lng,
+                "{} {} {} {:?}",
+                lat,
+                lng,
+                reference,
+                b
+            );
+        }
+    }
+}
+
+#[test]
+fn enclosing_hash() {
+    for reference in [
+        "31UDQ4825111943",
+        "31UDQ48251194",
+        "31UDQ",
+        "56HLH349522",
+        "ZAH",
+    ] {
+        let hash = mgrs::enclosing_hash(reference).unwrap();
+        let b = mgrs::decode(reference).unwrap();
+        assert!(geohash::bounding_box(&hash).contains_box(&b));
+        for c in geohash::base32::BASE32_ENCODING.iter() {
+            if hash.len() < 12 {
+                let child = format!("{}{}", hash, *c as char);
+                assert!(!geohash::bounding_box(&child).contains_box(&b));
+            }
+        }
+    }
+    assert!(mgrs::enclosing_hash("31UDQ4825111943")
+        .unwrap()
+        .starts_with("u09tunq"));
+}
@@ -1,5 +1,26 @@
+mod alphabet;
+mod altitude;
+mod cellunion;
+mod components;
+mod cover;
 mod decode_cases;
+mod distance;
 mod extensive;
+mod geodesic;
 mod geohash;
+mod hilbert;
+mod maidenhead;
+mod mgrs;
+mod morton;
 mod neighbors_test_cases;
+mod olc;
+mod outline;
+mod point;
+mod ranges;
+mod s2;
+mod space;
+mod spacetime;
 mod test_cases;
+mod tile;
+mod topology;
+mod utm;
@@ -0,0 +1,113 @@
+use crate::morton;
+
+/// naive interleaves the coordinates one bit at a time.
+fn naive(coords: &[u64], bits: usize) -> u128 {
+    let mut key = 0u128;
+    for i in 0..bits {
+        for (a, &c) in coords.iter().enumerate() {
+            key |= ((c >> i & 1) as u128) << (coords.len() * i + a);
+        }
+    }
+    key
+}
+
+#[test]
+fn encode() {
+    assert!(morton::encode2(0b11, 0b01, 32) == 0b0111);
+    assert!(morton::encode2(u32::MAX, 0, 32) == 0x5555555555555555);
+    assert!(morton::encode3(1, 1, 1, 21) == 0b111);
+    assert!(morton::encode3(0b10, 0, 0b10, 21) == 0b101000);
+    assert!(morton::encode4(0, 0, 0, 1, 16) == 0b1000);
+    // Bits above the width are dropped.
+    assert!(morton::encode2(0b111, 0, 2) == 0b0101);
+    assert!(morton::encode3(u32::MAX, 0, 0, 21) == 0x1249249249249249);
+    assert!(morton::encode4_128(u32::MAX, 0, 0, 0, 32) == naive(&[u32::MAX as u64, 0, 0, 0], 32));
+}
+
+#[test]
+fn random_roundtrips() {
+    for _ in 0..10_000 {
+        let bits = 1 + rand::random::<usize>() % 64;
+        let c: Vec<u64> = (0..4).map(|_| rand::random::<u64>()).collect();
+        let m = |n: usize| {
+            if bits.min(n) == 64 {
+                u64::MAX
+            } else {
+                (1 << bits.min(n)) - 1
+            }
+        };
+
+        let key = morton::encode2(c[0] as u32, c[1] as u32, bits);
+        assert!(key as u128 == naive(&[c[0] & m(32), c[1] & m(32)], bits.min(32)));
+        assert!(morton::decode2(key, bits) == ((c[0] & m(32)) as u32, (c[1] & m(32)) as u32));
+
+        let key = morton::encode3(c[0] as u32, c[1] as u32, c[2] as u32, bits);
+        let (x, y, z) = (
+            c[0] as u32 as u64 & m(21),
+            c[1] as u32 as u64 & m(21),
+            c[2] as u32 as u64 & m(21),
+        );
+        assert!(key as u128 == naive(&[x, y, z], bits.min(21)));
+        assert!(morton::decode3(key, bits) == (x as u32, y as u32, z as u32));
+
+        let key = morton::encode4(c[0] as u16, c[1] as u16, c[2] as u16, c[3] as u16, bits);
+        let d: Vec<u64> = c.iter().map(|&v| v as u16 as u64 & m(16)).collect();
+        assert!(key as u128 == naive(&d, bits.min(16)));
+        assert!(morton::decode4(key, bits) == (d[0] as u16, d[1] as u16, d[2] as u16, d[3] as u16));
+
+        let key = morton::encode2_128(c[0], c[1], bits);
+        assert!(key == naive(&[c[0] & m(64), c[1] & m(64)], bits));
+        assert!(morton::decode2_128(key, bits) == (c[0] & m(64), c[1] & m(64)));
+
+        let key = morton::encode3_128(c[0], c[1], c[2], bits);
+        let (x, y, z) = (c[0] & m(42), c[1] & m(42), c[2] & m(42));
+        assert!(key == naive(&[x, y, z], bits.min(42)));
+        assert!(morton::decode3_128(key, bits) == (x, y, z));
+
+        let key = morton::encode4_128(c[0] as u32, c[1] as u32, c[2] as u32, c[3] as u32, bits);
+        let d: Vec<u64> = c.iter().map(|&v| v as u32 as u64 & m(32)).collect();
+        assert!(key == naive(&d, bits.min(32)));
+        assert!(
+            morton::decode4_128(key, bits) == (d[0] as u32, d[1] as u32, d[2] as u32, d[3] as u32)
+        );
+    }
+}
+
+#[test]
+fn increment_decrement() {
+    for _ in 0..10_000 {
+        let bits = 1 + rand::random::<usize>() % 16;
+        let max = (1u32 << bits) - 1;
+        let (x, y, z) = (
+            rand::random::<u32>() & max,
+            rand::random::<u32>() & max,
+            rand::random::<u32>() & max,
+        );
+        let key = morton::encode3(x, y, z, bits);
+        let inc = |v: u32| if v == max { 0 } else { v + 1 };
+        let dec = |v: u32| if v == 0 { max } else { v - 1 };
+        assert!(morton::increment(key, 3, bits, 0) == morton::encode3(inc(x), y, z, bits));
+        assert!(morton::increment(key, 3, bits, 2) == morton::encode3(x, y, inc(z), bits));
+        assert!(morton::decrement(key, 3, bits, 1) == morton::encode3(x, dec(y), z, bits));
+
+        let key = morton::encode2_128(x as u64, y as u64, bits);
+        assert!(
+            morton::increment_128(key, 2, bits, 1)
+                == morton::encode2_128(x as u64, inc(y) as u64, bits)
+        );
+        assert!(
+            morton::decrement_128(key, 2, bits, 0)
+                == morton::encode2_128(dec(x) as u64, y as u64, bits)
+        );
+    }
+    // Full width keys wrap around too.
+    assert!(morton::increment(u64::MAX, 2, 32, 0) == 0xaaaaaaaaaaaaaaaa);
+    assert!(morton::decrement(0, 4, 16, 3) == 0x8888888888888888);
+    assert!(morton::increment_128(0, 3, 42, 2) == 0b100);
+}
+
+#[test]
+#[should_panic(expected = "axis 0 out of range for 0 dimensions")]
+fn increment_no_dims() {
+    morton::increment(0, 0, 32, 0);
+}
@@ -6,7 +6,7 @@ impl<'a> Iterator for Iter<'a> {
     fn next(&mut self) -> Option<NeighborsTestCase> {
         match self.data.find("},\n") {
             Some(index) => {
-                let line = &(&self.data[..index + 1]).trim();
+                let line = &self.data[..index + 1].trim();
                 // println!("-----------\n{}\n----------", line);
                 let case = NeighborsTestCase {
                     lat: line[1..]
@@ -67,11 +67,10 @@ impl<'a> Iterator for Iter<'a> {
 }
 
 pub fn iter<'a>() -> Iter<'a> {
-    Iter {
-        data: DATA,
-    }
+    Iter { data: DATA }
 }
 #[derive(Debug)]
+#[allow(dead_code)]
 pub struct NeighborsTestCase {
     pub lat: f64,
     pub lng: f64,
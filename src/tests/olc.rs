@@ -0,0 +1,109 @@
+use crate as geohash;
+use crate::olc;
+
+#[test]
+fn encode() {
+    assert!(olc::encode(20.375, 2.775, 6) == "7FG49Q00+");
+    assert!(olc::encode(20.3700625, 2.7821875, 10) == "7FG49QCJ+2V");
+    assert!(olc::encode(20.3701125, 2.782234375, 11) == "7FG49QCJ+2VX");
+    assert!(olc::encode(20.3701135, 2.78223535156, 13) == "7FG49QCJ+2VXGJ");
+    assert!(olc::encode(47.0000625, 8.0000625, 10) == "8FVC2222+22");
+    // Odd lengths below 10 are rounded up.
+    assert!(olc::encode(20.375, 2.775, 5) == "7FG49Q00+");
+    // Latitude 90 belongs to the northernmost cell, longitude 180 wraps.
+    assert!(olc::encode(90.0, 1.0, 4) == "CFX30000+");
+    assert!(olc::encode(1.0, 180.0, 4) == olc::encode(1.0, -180.0, 4));
+}
+
+#[test]
+fn decode() {
+    let b = olc::decode("7FG49QCJ+2V").unwrap();
+    assert!((b.min_lat - 20.37).abs() < 1e-10 && (b.max_lat - 20.370125).abs() < 1e-10);
+    assert!((b.min_lng - 2.782125).abs() < 1e-10 && (b.max_lng - 2.78225).abs() < 1e-10);
+    let b = olc::decode("7fg49q00+").unwrap();
+    assert!((b.min_lat - 20.35).abs() < 1e-10 && (b.max_lat - 20.4).abs() < 1e-10);
+    let b = olc::decode("7FG49QCJ+2VX").unwrap();
+    assert!((b.min_lat - 20.3701).abs() < 1e-10 && (b.min_lng - 2.78221875).abs() < 1e-10);
+    for _ in 0..100 {
+        let lat = -90.0 + 180.0 * rand::random::<f64>();
+        let lng = -180.0 + 360.0 * rand::random::<f64>();
+        let length = 2 + rand::random::<usize>() % 14;
+        let b = olc::decode(&olc::encode(lat, lng, length)).unwrap();
+        assert!(b.min_lat - 1e-9 <= lat && lat < b.max_lat + 1e-9);
+        assert!(b.min_lng - 1e-9 <= lng && lng < b.max_lng + 1e-9);
+    }
+    assert!(olc::decode("9G8F+6X").is_err());
+}
+
+#[test]
+fn validate() {
+    for code in [
+        "8FWC2345+G6",
+        "8FWC2345+G6G",
+        "8fwc2345+",
+        "8FWCX400+",
+        "WC2345+G6g",
+        "2345+G6",
+        "45+G6",
+        "+G6",
+    ] {
+        assert!(olc::validate(code).is_ok(), "{}", code);
+    }
+    for code in [
+        "G+",
+        "+",
+        "8FWC2345+G",
+        "8FWC2_45+G6",
+        "8FWC2η45+G6",
+        "8FWC2345+G6+",
+        "8FWC2345G6+",
+        "8FWC2300+G6",
+        "WC2300+G6g",
+        "WC2345+G",
+    ] {
+        assert!(olc::validate(code).is_err(), "{}", code);
+    }
+    assert!(olc::is_full("8FWC2345+G6") && !olc::is_short("8FWC2345+G6"));
+    assert!(olc::is_short("2345+G6") && !olc::is_full("2345+G6"));
+    // The first digits exceed the range of latitude and longitude.
+    assert!(!olc::is_full("WC2345G6+"));
+    assert!(!olc::is_full("CWCW2345+G6") && !olc::is_full("X2345678+"));
+}
+
+#[test]
+fn short_codes() {
+    assert!(olc::recover_nearest("9G8F+6X", 47.4, 8.6) == Ok("8FVC9G8F+6X".to_owned()));
+    assert!(olc::shorten("9C3W9QCJ+2VX", 51.3701125, -1.217765625) == Ok("+2VX".to_owned()));
+    assert!(olc::shorten("9C3W9QCJ+2VX", 51.3708675, -1.217765625) == Ok("CJ+2VX".to_owned()));
+    assert!(olc::shorten("8FVC0000+", 47.4, 8.6).is_err());
+    // Recovering near the antimeridian and the poles.
+    let full = olc::encode(10.0, 179.99, 10);
+    let short = &full[4..];
+    assert!(olc::recover_nearest(short, 10.0, -179.99).unwrap() == full);
+    let full = olc::encode(89.99, 0.0, 10);
+    assert!(olc::recover_nearest(&full[4..], 89.9, 0.0).unwrap() == full);
+    for _ in 0..100 {
+        let lat = -80.0 + 160.0 * rand::random::<f64>();
+        let lng = -180.0 + 360.0 * rand::random::<f64>();
+        let full = olc::encode(lat, lng, 10 + rand::random::<usize>() % 4);
+        let (ref_lat, ref_lng) = (lat + 0.01, lng - 0.01);
+        let short = olc::shorten(&full, ref_lat, ref_lng).unwrap();
+        assert!(short.len() < full.len());
+        assert!(olc::recover_nearest(&short, ref_lat, ref_lng).unwrap() == full);
+    }
+}
+
+#[test]
+fn geohash_cover() {
+    let code = "8FVC9G8F+6X";
+    let b = olc::decode(code).unwrap();
+    let hashes = olc::to_geohash_cover(code, 7).unwrap();
+    let (lat, lng) = b.center();
+    assert!(hashes.contains(&geohash::encode_with_precision(lat, lng, 7)));
+    for hash in &hashes {
+        assert!(geohash::bounding_box(hash).intersects(&b));
+    }
+    assert!(olc::to_geohash_cover(code, 3).unwrap().len() == 1);
+    assert!(olc::to_geohash_cover("9G8F+6X", 3).is_err());
+    assert!(olc::to_geohash_cover(code, 13).is_err());
+}
@@ -0,0 +1,126 @@
+use crate as geohash;
+use crate::cover::Polygon;
+use crate::outline::{outline, outline_int};
+
+fn corners(b: &geohash::Box) -> Vec<(f64, f64)> {
+    vec![
+        (b.min_lat, b.min_lng),
+        (b.min_lat, b.max_lng),
+        (b.max_lat, b.max_lng),
+        (b.max_lat, b.min_lng),
+    ]
+}
+
+/// same_ring decides whether two rings have the same vertices in the same
+/// order, starting anywhere.
+fn same_ring(a: &[(f64, f64)], b: &[(f64, f64)]) -> bool {
+    a.len() == b.len() && (0..a.len()).any(|k| (0..a.len()).all(|i| a[(i + k) % a.len()] == b[i]))
+}
+
+#[test]
+fn single_cell() {
+    let polygons = outline(&["9q8yy"]);
+    assert!(polygons.len() == 1 && polygons[0].holes.is_empty());
+    let b = geohash::bounding_box("9q8yy");
+    assert!(same_ring(&polygons[0].exterior, &corners(&b)));
+    assert!(outline::<&str>(&[]).is_empty());
+}
+
+#[test]
+// Cells sharing edges merge, and collinear vertices are removed.
+fn merged_cells() {
+    let east = geohash::neighbor("9q8yy", geohash::EAST);
+    let polygons = outline(&["9q8yy", east.as_str()]);
+    assert!(polygons.len() == 1);
+    let a = geohash::bounding_box("9q8yy");
+    let b = geohash::bounding_box(&east);
+    let expected = geohash::Box {
+        max_lng: b.max_lng,
+        ..a
+    };
+    assert!(same_ring(&polygons[0].exterior, &corners(&expected)));
+}
+
+#[test]
+fn hole() {
+    let mut hashes: Vec<String> = geohash::neighbors("9q8yy").to_vec();
+    let polygons = outline(&hashes);
+    assert!(polygons.len() == 1 && polygons[0].holes.len() == 1);
+    let b = geohash::bounding_box("9q8yy");
+    assert!(same_ring(
+        &polygons[0].holes[0],
+        &corners(&b).into_iter().rev().collect::<Vec<_>>()
+    ));
+    let (lat, lng) = b.center();
+    assert!(!polygons[0].contains(lat, lng));
+
+    // An island inside the hole is a polygon of its own.
+    hashes.push("9q8yyk".to_owned());
+    let polygons = outline(&hashes);
+    assert!(polygons.len() == 2);
+    assert!(polygons.iter().filter(|p| p.holes.len() == 1).count() == 1);
+    let (lat, lng) = geohash::decode_center("9q8yyk");
+    assert!(polygons.iter().any(|p| p.contains(lat, lng)));
+}
+
+#[test]
+fn corner() {
+    // Cells touching only at a corner stay apart.
+    let ne = geohash::neighbor("9q8yy", geohash::NORTH_EAST);
+    let polygons = outline(&["9q8yy", ne.as_str()]);
+    assert!(polygons.len() == 2);
+    assert!(polygons
+        .iter()
+        .all(|p| p.exterior.len() == 4 && p.holes.is_empty()));
+}
+
+#[test]
+// The outline must be the boundary of exactly the union of the cells.
+fn mixed_precision() {
+    let hashes = ["9q8y", "9q8zh", "9q8zk", "9q9p", "9q8v"];
+    let polygons = outline(&hashes);
+    let inside = |lat: f64, lng: f64| polygons.iter().any(|p: &Polygon| p.contains(lat, lng));
+    let b = geohash::bounding_box("9q8");
+    for _ in 0..2000 {
+        let lat = b.min_lat - 0.2 + (b.max_lat - b.min_lat + 0.4) * rand::random::<f64>();
+        let lng = b.min_lng - 0.2 + (b.max_lng - b.min_lng + 0.4) * rand::random::<f64>();
+        let hash = geohash::encode(lat, lng);
+        let expected = hashes.iter().any(|h| hash.starts_with(h));
+        assert!(inside(lat, lng) == expected, "{},{}", lat, lng);
+    }
+    for p in &polygons {
+        for ring in std::iter::once(&p.exterior).chain(p.holes.iter()) {
+            let n = ring.len();
+            for i in 0..n {
+                let (a, b, c) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+                assert!(!(a.0 == b.0 && b.0 == c.0 || a.1 == b.1 && b.1 == c.1));
+            }
+        }
+    }
+}
+
+#[test]
+fn integer_cells() {
+    // The western half of the world.
+    let polygons = outline_int(&[(0b00, 2), (0b01, 2)]);
+    assert!(polygons.len() == 1);
+    let b = geohash::Box {
+        min_lat: -90.0,
+        max_lat: 90.0,
+        min_lng: -180.0,
+        max_lng: 0.0,
+    };
+    assert!(same_ring(&polygons[0].exterior, &corners(&b)));
+    let world = outline_int(&[(0, 0)]);
+    assert!(same_ring(
+        &world[0].exterior,
+        &corners(&geohash::bounding_box(""))
+    ));
+}
+
+#[test]
+fn antimeridian() {
+    let west = geohash::encode_with_precision(0.0, 179.99, 3);
+    let east = geohash::encode_with_precision(0.0, -179.99, 3);
+    assert!(outline(&[west, east]).len() == 2);
+}
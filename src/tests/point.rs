@@ -0,0 +1,77 @@
+use crate as geohash;
+use crate::cover::{self, Circle, CoverOptions, Polygon};
+use crate::point::{self, LatLng, LatLngTuple, LngLatTuple, Point};
+
+#[test]
+fn tuples() {
+    let p = Point::new(42.6, -5.6);
+    let latlng = LatLngTuple(42.6, -5.6);
+    let lnglat = LngLatTuple(-5.6, 42.6);
+    assert!(latlng.lat() == 42.6 && latlng.lng() == -5.6);
+    assert!(lnglat.lat() == 42.6 && lnglat.lng() == -5.6);
+    assert!(Point::from(latlng) == p);
+    assert!(Point::from(lnglat) == p);
+    assert!(LatLngTuple::from(p) == latlng);
+    assert!(LngLatTuple::from(p) == lnglat);
+}
+
+#[test]
+// Every order of a point encodes the same as the positional API.
+fn encode() {
+    let (lat, lng) = (42.6, -5.6);
+    let hash = geohash::encode(lat, lng);
+    assert!(point::encode(Point::new(lat, lng)) == hash);
+    assert!(point::encode(LatLngTuple(lat, lng)) == hash);
+    assert!(point::encode(LngLatTuple(lng, lat)) == hash);
+    assert!(point::encode_with_precision(Point::new(lat, lng), 5) == "ezs42");
+    assert!(point::encode_int(LngLatTuple(lng, lat)) == geohash::encode_int(lat, lng));
+    assert!(
+        point::encode_int_with_precision(LngLatTuple(lng, lat), 26)
+            == geohash::encode_int_with_precision(lat, lng, 26)
+    );
+    // References to points are points too.
+    let points = [Point::new(lat, lng), Point::new(-lat, -lng)];
+    let hashes: Vec<String> = points.iter().map(point::encode).collect();
+    assert!(hashes == [hash, geohash::encode(-lat, -lng)]);
+    let (lat, lng) = geohash::decode("ezs42");
+    assert!(point::decode("ezs42") == Point::new(lat, lng));
+    let (lat, lng) = geohash::decode_center("ezs42");
+    assert!(point::decode_center("ezs42") == Point::new(lat, lng));
+}
+
+#[test]
+fn neighbors() {
+    let p = LngLatTuple(-5.6, 42.6);
+    assert!(point::neighbors(p, 5) == geohash::neighbors("ezs42"));
+    let hash = geohash::encode_int_with_precision(42.6, -5.6, 26);
+    assert!(
+        point::neighbors_int_with_precision(p, 26)
+            == geohash::neighbors_int_with_precision(hash, 26)
+    );
+}
+
+#[test]
+fn covers() {
+    let options = CoverOptions {
+        min_level: 1,
+        max_level: 5,
+        max_cells: 64,
+    };
+    let cells = cover::cover(&Point::new(42.6, -5.6), &options);
+    assert!(cells == ["ezs42"]);
+    assert!(cover::interior_cover(&Point::new(42.6, -5.6), &options).is_empty());
+
+    let circle = Circle::around(LngLatTuple(-5.6, 42.6), 1000.0);
+    assert!(circle == Circle::new(42.6, -5.6, 1000.0));
+
+    let ring = vec![
+        LngLatTuple(-6.0, 42.0),
+        LngLatTuple(-5.0, 42.0),
+        LngLatTuple(-5.0, 43.0),
+        LngLatTuple(-6.0, 43.0),
+    ];
+    let polygon = Polygon::from_points(ring);
+    assert!(polygon.exterior[1] == (42.0, -5.0));
+    assert!(polygon.contains_point(Point::new(42.6, -5.6)));
+    assert!(!polygon.contains_point(LatLngTuple(-5.6, 42.6)));
+}
@@ -0,0 +1,275 @@
+use super::test_cases;
+use crate as geohash;
+
+fn random_point() -> (f64, f64) {
+    (
+        -90.0 + 180.0 * rand::random::<f64>(),
+        -180.0 + 360.0 * rand::random::<f64>(),
+    )
+}
+
+fn random_box() -> geohash::Box {
+    let (lat, lng) = random_point();
+    let lat_size = 10.0 * rand::random::<f64>();
+    let lng_size = 10.0 * rand::random::<f64>();
+    geohash::Box {
+        min_lat: lat,
+        max_lat: (lat + lat_size).min(90.0),
+        min_lng: lng,
+        max_lng: (lng + lng_size).min(180.0),
+    }
+}
+
+fn in_ranges(ranges: &[(u64, u64)], key: u64) -> bool {
+    ranges
+        .iter()
+        .any(|&(first, last)| first <= key && key <= last)
+}
+
+#[test]
+// Every point inside the box must fall into one of the ranges.
+fn box_to_ranges_superset() {
+    for _ in 0..100 {
+        let b = random_box();
+        let ranges = geohash::box_to_ranges(&b, 40, 16);
+        for _ in 0..100 {
+            let lat = b.min_lat + (b.max_lat - b.min_lat) * rand::random::<f64>();
+            let lng = b.min_lng + (b.max_lng - b.min_lng) * rand::random::<f64>();
+            let key = geohash::encode_int(lat, lng);
+            assert!(
+                in_ranges(&ranges, key),
+                "point {},{} in {:?} is not covered by {:?}",
+                lat,
+                lng,
+                b,
+                ranges
+            );
+        }
+    }
+}
+
+#[test]
+// Ranges must be sorted, disjoint and no more than requested.
+fn box_to_ranges_limits() {
+    for max_ranges in 1..20 {
+        let b = random_box();
+        let ranges = geohash::box_to_ranges(&b, 64, max_ranges);
+        assert!(!ranges.is_empty() && ranges.len() <= max_ranges);
+        for w in ranges.windows(2) {
+            assert!(
+                w[0].0 <= w[0].1 && w[0].1 + 1 < w[1].0,
+                "bad ranges {:?}",
+                ranges
+            );
+        }
+    }
+}
+
+#[test]
+// Refining with more ranges never covers more of the key space.
+fn box_to_ranges_accuracy() {
+    let b = random_box();
+    let size =
+        |ranges: &[(u64, u64)]| -> u128 { ranges.iter().map(|&(f, l)| (l - f) as u128 + 1).sum() };
+    let coarse = geohash::box_to_ranges(&b, 32, 4);
+    let fine = geohash::box_to_ranges(&b, 32, 64);
+    assert!(size(&fine) <= size(&coarse));
+}
+
+#[test]
+fn box_to_ranges_cell() {
+    let b = geohash::bounding_box("9q8yy");
+    let start = geohash::base32::decode(b"9q8yy") << 39;
+    let last = start + (1 << 39) - 1;
+    let ranges = geohash::box_to_ranges(&b, 25, 1);
+    assert!(ranges.len() == 1);
+    assert!(ranges[0].0 <= start && last <= ranges[0].1);
+    let ranges = geohash::box_to_ranges(&b, 64, 64);
+    assert!(ranges.iter().any(|&(f, l)| f == start && l >= last));
+}
+
+#[test]
+fn box_to_ranges_world() {
+    let b = geohash::Box {
+        min_lat: -90.0,
+        max_lat: 90.0,
+        min_lng: -180.0,
+        max_lng: 180.0,
+    };
+    assert!(geohash::box_to_ranges(&b, 64, 8) == vec![(0, u64::MAX)]);
+    // The last key of the key space is covered too.
+    let b = geohash::Box {
+        min_lat: 89.0,
+        max_lat: 90.0,
+        min_lng: 179.0,
+        max_lng: 180.0,
+    };
+    let key = geohash::encode_int(90.0, 180.0);
+    assert!(key == u64::MAX);
+    assert!(in_ranges(&geohash::box_to_ranges(&b, 64, 8), key));
+}
+
+#[test]
+// A box across the antimeridian covers both of its sides.
+fn box_to_ranges_antimeridian() {
+    let b = geohash::Box {
+        min_lat: -10.0,
+        max_lat: 10.0,
+        min_lng: 170.0,
+        max_lng: -170.0,
+    };
+    let ranges = geohash::box_to_ranges(&b, 40, 16);
+    assert!(!ranges.is_empty() && ranges.len() <= 16);
+    for _ in 0..1000 {
+        let lat = -10.0 + 20.0 * rand::random::<f64>();
+        let lng = 170.0 + 20.0 * rand::random::<f64>();
+        let lng = if lng >= 180.0 { lng - 360.0 } else { lng };
+        assert!(in_ranges(&ranges, geohash::encode_int(lat, lng)));
+    }
+    assert!(!in_ranges(&ranges, geohash::encode_int(0.0, 0.0)));
+}
+
+#[test]
+// Every full hash inside a cell must lie in the prefix range.
+fn prefix_range() {
+    for c in test_cases::iter() {
+        for chars in 0..13 {
+            let (min, max) = geohash::prefix_range(&c.hash[..chars]);
+            assert!(
+                min <= c.hash_int && c.hash_int <= max,
+                "{} not in prefix range of {}",
+                c.hash_int,
+                &c.hash[..chars]
+            );
+        }
+    }
+    assert!(geohash::prefix_range("") == (0, u64::MAX));
+    let (min, max) = geohash::prefix_range("9q8yy");
+    assert!(min == geohash::base32::decode(b"9q8yy") << 39);
+    assert!(max == min + (1 << 39) - 1);
+}
+
+#[test]
+fn int_prefix_range() {
+    assert!(geohash::int_prefix_range(0b101, 3, 6) == (0b101000, 0b101111));
+    assert!(geohash::int_prefix_range(0b101, 3, 3) == (0b101, 0b101));
+    assert!(geohash::int_prefix_range(0b101, 3, 2) == (0b10, 0b10));
+    assert!(geohash::int_prefix_range(0, 0, 64) == (0, u64::MAX));
+    let hash = geohash::encode_int_with_precision(42.6, -5.6, 26);
+    let (min, max) = geohash::int_prefix_range(hash, 26, 64);
+    let key = geohash::encode_int(42.6, -5.6);
+    assert!(min <= key && key <= max);
+}
+
+#[test]
+// Converting a prefix to a range and back must be lossless.
+fn range_prefix() {
+    for c in test_cases::iter() {
+        for chars in 0..13 {
+            let hash = &c.hash[..chars];
+            let (min, max) = geohash::prefix_range(hash);
+            assert!(geohash::range_prefix(min, max).as_deref() == Some(hash));
+        }
+        for bits in 0..65 {
+            let hash = c.hash_int.checked_shr(64 - bits as u32).unwrap_or(0);
+            let (min, max) = geohash::int_prefix_range(hash, bits, 64);
+            assert!(geohash::int_range_prefix(min, max, 64) == Some((hash, bits)));
+        }
+    }
+    // Not aligned to a string geohash.
+    let (min, max) = geohash::int_prefix_range(0b101, 3, 64);
+    assert!(geohash::range_prefix(min, max).is_none());
+    // Not aligned at all.
+    assert!(geohash::int_range_prefix(1, 2, 8).is_none());
+    assert!(geohash::int_range_prefix(0, 2, 8).is_none());
+    assert!(geohash::int_range_prefix(4, 7, 8) == Some((1, 6)));
+    assert!(geohash::int_range_prefix(0, 256, 8).is_none());
+}
+
+#[test]
+fn truncate_extend_bits() {
+    use geohash::Descendant::{Max, Min};
+    assert!(geohash::truncate_bits(0b10110, 5, 3) == 0b101);
+    assert!(geohash::truncate_bits(0b10110, 5, 5) == 0b10110);
+    assert!(geohash::truncate_bits(0b10110, 5, 8) == 0b10110);
+    assert!(geohash::extend_bits(0b101, 3, 6, Min) == 0b101000);
+    assert!(geohash::extend_bits(0b101, 3, 6, Max) == 0b101111);
+    assert!(geohash::extend_bits(0b101, 3, 2, Max) == 0b10);
+    for c in test_cases::iter() {
+        for bits in 0..65 {
+            let hash = geohash::truncate_bits(c.hash_int, 64, bits);
+            assert!(geohash::extend_bits(hash, bits, 64, Min) <= c.hash_int);
+            assert!(c.hash_int <= geohash::extend_bits(hash, bits, 64, Max));
+        }
+    }
+}
+
+#[test]
+fn int_to_string() {
+    use geohash::LeftoverBits::{Cover, Error, Truncate};
+    for c in test_cases::iter() {
+        for chars in 0..13 {
+            let hash = geohash::truncate_bits(c.hash_int, 64, 5 * chars);
+            for &leftover in [Error, Truncate, Cover].iter() {
+                let strings = geohash::int_to_string(hash, 5 * chars, leftover).unwrap();
+                assert!(strings == [c.hash[..chars].to_owned()]);
+            }
+            let hash = geohash::string_to_int(&c.hash[..chars], 5 * chars).unwrap();
+            assert!(hash == geohash::truncate_bits(c.hash_int, 64, 5 * chars));
+        }
+    }
+    let hash = geohash::string_to_int("9q8yy", 26).unwrap();
+    assert!(geohash::int_to_string(hash, 26, Error).is_err());
+    assert!(geohash::int_to_string(hash, 26, Truncate).unwrap() == ["9q8yy"]);
+    let cover = geohash::int_to_string(hash, 26, Cover).unwrap();
+    assert!(cover.len() == 16);
+    assert!(cover.iter().all(|s| s.starts_with("9q8yy")));
+    let (min, max) = geohash::int_prefix_range(hash, 26, 64);
+    assert!(geohash::prefix_range(&cover[0]).0 == min);
+    assert!(geohash::prefix_range(&cover[15]).1 == max);
+    assert!(geohash::int_to_string(0, 61, Cover).is_err());
+    assert!(geohash::int_to_string(0, 65, Truncate).is_err());
+    // Extending gives the first descendant.
+    assert!(geohash::string_to_int("9", 8).unwrap() == 0b01001000);
+    assert!(geohash::string_to_int("9q", 3).unwrap() == 0b010);
+    assert!(geohash::string_to_int("9a", 10).is_err());
+}
+
+// Brute force a small box in Z-order space against bigmin and litmax.
+#[test]
+fn bigmin_litmax() {
+    for _ in 0..50 {
+        let (lat, lng) = random_point();
+        let base = geohash::encode_int(lat, lng) & !0xff;
+        let (base_lat, base_lng) = geohash::deinterleave(base);
+        let (a, b) = (rand::random::<u32>() % 16, rand::random::<u32>() % 16);
+        let (c, d) = (rand::random::<u32>() % 16, rand::random::<u32>() % 16);
+        let min = geohash::interleave(base_lat + a.min(b), base_lng + c.min(d));
+        let max = geohash::interleave(base_lat + a.max(b), base_lng + c.max(d));
+        let inside = |z: u64| {
+            let (lat, lng) = geohash::deinterleave(z);
+            let (min_lat, min_lng) = geohash::deinterleave(min);
+            let (max_lat, max_lng) = geohash::deinterleave(max);
+            min_lat <= lat && lat <= max_lat && min_lng <= lng && lng <= max_lng
+        };
+        for z in base..base + 0x100 {
+            let next = (z..=max).find(|&z| inside(z));
+            assert!(geohash::bigmin(z, min, max) == next, "bigmin of {:x}", z);
+            let prev = (min..=z).rev().find(|&z| inside(z));
+            assert!(geohash::litmax(z, min, max) == prev, "litmax of {:x}", z);
+        }
+    }
+}
+
+#[test]
+fn box_int_corners() {
+    let b = geohash::bounding_box("9q8yy");
+    let (min, max) = geohash::box_int_corners(&b);
+    let key = geohash::encode_int(37.77, -122.42);
+    let next = geohash::bigmin(key, min, max).unwrap();
+    assert!(b.contains(geohash::decode_int(next).0, geohash::decode_int(next).1));
+    assert!(geohash::bigmin(max + 1, min, max).is_none());
+    assert!(geohash::litmax(min - 1, min, max).is_none());
+    assert!(geohash::bigmin(0, min, max) == Some(min));
+    assert!(geohash::litmax(u64::MAX, min, max) == Some(max));
+}
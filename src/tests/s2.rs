@@ -0,0 +1,134 @@
+use crate as geohash;
+use crate::s2::{self, CellId};
+
+fn random_point() -> (f64, f64) {
+    let lat = -89.0 + 178.0 * rand::random::<f64>();
+    let lng = -180.0 + 360.0 * rand::random::<f64>();
+    (lat, lng)
+}
+
+fn box_contains(b: &geohash::Box, lat: f64, lng: f64) -> bool {
+    let eps = 1e-9;
+    let in_lat = b.min_lat - eps <= lat && lat <= b.max_lat + eps;
+    let in_lng = if b.min_lng <= b.max_lng {
+        b.min_lng - eps <= lng && lng <= b.max_lng + eps
+    } else {
+        lng >= b.min_lng - eps || lng <= b.max_lng + eps
+    };
+    in_lat && in_lng
+}
+
+#[test]
+fn faces() {
+    let points = [
+        (0.0, 0.0),
+        (0.0, 90.0),
+        (90.0, 0.0),
+        (0.0, 180.0),
+        (0.0, -90.0),
+        (-90.0, 0.0),
+    ];
+    let tokens = ["1", "3", "5", "7", "9", "b"];
+    for (face, &(lat, lng)) in points.iter().enumerate() {
+        let cell = CellId::from_lat_lng_at_level(lat, lng, 0);
+        assert!(cell == CellId::from_face(face as u8));
+        assert!(cell.face() == face as u8);
+        assert!(cell.level() == 0);
+        assert!(cell.to_token() == tokens[face]);
+        assert!(CellId::from_face_ij(face as u8, 0, 0) == cell.range_min());
+    }
+}
+
+#[test]
+fn hierarchy() {
+    let leaf = CellId::from_lat_lng(37.7749, -122.4194);
+    assert!(leaf.is_valid());
+    assert!(leaf.is_leaf());
+    assert!(leaf.level() == s2::MAX_LEVEL);
+    for level in 0..s2::MAX_LEVEL {
+        let cell = leaf.parent(level);
+        assert!(cell.level() == level);
+        assert!(cell.contains(&leaf));
+        let children = cell.children();
+        assert!(children.iter().filter(|c| c.contains(&leaf)).count() == 1);
+        assert!(children.iter().all(|c| c.parent(level) == cell));
+        assert!(children[0].range_min() == cell.range_min());
+        assert!(children[3].range_max() == cell.range_max());
+    }
+    assert!(leaf.children() == [leaf; 4]);
+}
+
+#[test]
+fn tokens() {
+    let cell = CellId::from_lat_lng_at_level(48.8583, 2.2945, 12);
+    let token = cell.to_token();
+    assert!(token.len() <= 16 && !token.ends_with('0'));
+    assert!(CellId::from_token(&token).unwrap() == cell);
+    assert!(CellId::from_token(&token.to_uppercase()).unwrap() == cell);
+    assert!(CellId(0).to_token() == "X");
+    assert!(CellId::from_token("").is_err());
+    assert!(CellId::from_token("X").is_err());
+    assert!(CellId::from_token("c").is_err());
+    assert!(CellId::from_token("12").is_err());
+    // The marker bit may not be missing or lie among the face bits.
+    assert!(CellId::from_token("0").is_err());
+    assert!(CellId::from_token("4").is_err());
+    assert!(CellId::from_token("8").is_err());
+    assert!(!CellId(0).is_valid() && !CellId(1 << 62).is_valid());
+    assert!(CellId::from_token("0123456789abcdef0").is_err());
+}
+
+#[test]
+fn points_in_cells() {
+    for _ in 0..1000 {
+        let (lat, lng) = random_point();
+        let level = rand::random::<usize>() % (s2::MAX_LEVEL + 1);
+        let cell = CellId::from_lat_lng_at_level(lat, lng, level);
+        assert!(box_contains(&cell.bounding_box(), lat, lng));
+        let (clat, clng) = cell.center();
+        assert!(CellId::from_lat_lng_at_level(clat, clng, level) == cell);
+        for (vlat, vlng) in cell.vertices().iter() {
+            assert!(box_contains(&cell.bounding_box(), *vlat, *vlng));
+        }
+    }
+}
+
+#[test]
+fn bounding_boxes() {
+    // A cell on the antimeridian.
+    let b = CellId::from_lat_lng_at_level(10.0, 180.0, 4).bounding_box();
+    assert!(b.min_lng > b.max_lng);
+    assert!(box_contains(&b, 10.0, 180.0));
+    // A cell with a corner at the north pole.
+    let b = CellId::from_lat_lng_at_level(89.99, 45.0, 6).bounding_box();
+    assert!(b.max_lat == 90.0 && b.min_lng == -180.0 && b.max_lng == 180.0);
+    let b = CellId::from_face(3).bounding_box();
+    assert!(b.min_lng == 135.0 && b.max_lng == -135.0);
+}
+
+#[test]
+fn geohash_covers() {
+    for _ in 0..200 {
+        let (lat, lng) = random_point();
+        let cell = CellId::from_lat_lng_at_level(lat, lng, 8);
+        let hashes = s2::hashes_for_cell(&cell, 4);
+        assert!(hashes.iter().all(|h| h.len() == 4));
+        assert!(hashes.contains(&geohash::encode_with_precision(lat, lng, 4)));
+
+        let hash = geohash::encode_with_precision(lat, lng, 5);
+        let cells = s2::cells_for_hash(&hash, 10);
+        assert!(cells.iter().all(|c| c.level() == 10));
+        assert!(cells.contains(&CellId::from_lat_lng_at_level(lat, lng, 10)));
+    }
+}
+
+#[test]
+fn covers_are_tight() {
+    // Cells near a face center are close in size to geohash cells of the
+    // same area, so each needs few of the other. Cells that only touch the
+    // boundary are included.
+    let cells = s2::cells_for_hash("s00", 6);
+    assert!(!cells.is_empty() && cells.len() <= 16);
+    let hashes = s2::hashes_for_cell(&CellId::from_lat_lng_at_level(0.5, 0.5, 6), 3);
+    assert!(!hashes.is_empty() && hashes.len() <= 9);
+}
@@ -0,0 +1,115 @@
+use crate as geohash;
+use crate::cover::CoverOptions;
+use crate::space::GeohashSpace;
+
+/// A floor plan of 120 by 80 meters.
+fn floor() -> GeohashSpace {
+    GeohashSpace::new(0.0, 0.0, 120.0, 80.0).unwrap()
+}
+
+#[test]
+fn new() {
+    assert!(GeohashSpace::new(0.0, 0.0, 0.0, 1.0).is_err());
+    assert!(GeohashSpace::new(0.0, 1.0, 1.0, 0.0).is_err());
+    assert!(GeohashSpace::new(0.0, 0.0, f64::INFINITY, 1.0).is_err());
+    assert!(GeohashSpace::new(-180.0, -90.0, 180.0, 90.0).unwrap() == GeohashSpace::default());
+    assert!(floor().extent().max_lng == 120.0);
+}
+
+#[test]
+// The default space is the world of the global API.
+fn default_space() {
+    let world = GeohashSpace::default();
+    for _ in 0..1000 {
+        let lat = -90.0 + 180.0 * rand::random::<f64>();
+        let lng = -180.0 + 360.0 * rand::random::<f64>();
+        let chars = 1 + rand::random::<usize>() % 12;
+        let hash = geohash::encode_with_precision(lat, lng, chars);
+        assert!(world.encode_with_precision(lng, lat, chars) == hash);
+        assert!(world.encode_int(lng, lat) == geohash::encode_int(lat, lng));
+        assert!(world.bounding_box(&hash) == geohash::bounding_box(&hash));
+        let (x, y) = world.decode(&hash);
+        assert!((y, x) == geohash::decode(&hash));
+        assert!(world.neighbors(&hash) == geohash::neighbors(&hash));
+        let bits = 1 + rand::random::<usize>() % 64;
+        let int = geohash::encode_int_with_precision(lat, lng, bits);
+        assert!(
+            world.neighbors_int_with_precision(int, bits)
+                == geohash::neighbors_int_with_precision(int, bits)
+        );
+    }
+}
+
+#[test]
+fn encode_decode() {
+    let space = floor();
+    // The first character splits x in 8 and y in 4.
+    assert!(space.encode_with_precision(0.0, 0.0, 1) == "0");
+    assert!(space.encode_with_precision(119.0, 79.0, 1) == "z");
+    let b = space.bounding_box("0");
+    assert!(b.min_lng == 0.0 && b.max_lng == 15.0 && b.min_lat == 0.0 && b.max_lat == 20.0);
+    for _ in 0..1000 {
+        let x = 120.0 * rand::random::<f64>();
+        let y = 80.0 * rand::random::<f64>();
+        let chars = 1 + rand::random::<usize>() % 12;
+        let hash = space.encode_with_precision(x, y, chars);
+        assert!(space.bounding_box(&hash).contains(y, x));
+        let (dx, dy) = space.decode(&hash);
+        assert!(space.encode_with_precision(dx, dy, chars) == hash);
+        let (cx, cy) = space.decode_center(&hash);
+        assert!(space.encode_with_precision(cx, cy, chars) == hash);
+        let bits = 1 + rand::random::<usize>() % 64;
+        let int = space.encode_int_with_precision(x, y, bits);
+        assert!(space
+            .bounding_box_int_with_precision(int, bits)
+            .contains(y, x));
+        let (dx, dy) = space.decode_int_with_precision(int, bits);
+        assert!(space.encode_int_with_precision(dx, dy, bits) == int);
+    }
+    let (h, w) = space.error_with_precision(5);
+    assert!(h == 20.0 && w == 15.0);
+}
+
+#[test]
+fn neighbors() {
+    let space = floor();
+    let hash = space.encode_with_precision(60.0, 40.0, 4);
+    let b = space.bounding_box(&hash);
+    let n = space.neighbors(&hash);
+    let north = space.bounding_box(&n[geohash::NORTH]);
+    assert!(north.min_lat == b.max_lat && north.min_lng == b.min_lng);
+    let west = space.bounding_box(&n[geohash::WEST]);
+    assert!(west.max_lng == b.min_lng && west.min_lat == b.min_lat);
+    // The extent does not wrap around.
+    let corner = space.encode_with_precision(0.0, 0.0, 4);
+    assert!(space.neighbors(&corner)[geohash::SOUTH_WEST] == corner);
+}
+
+#[test]
+fn covers() {
+    let space = floor();
+    let room = geohash::Box {
+        min_lat: 10.0,
+        max_lat: 25.0,
+        min_lng: 30.0,
+        max_lng: 50.0,
+    };
+    let options = CoverOptions {
+        min_level: 1,
+        max_level: 4,
+        max_cells: 16,
+    };
+    let cells = space.cover(&room, &options);
+    assert!(!cells.is_empty() && cells.len() <= 16);
+    for _ in 0..1000 {
+        let x = 30.0 + 20.0 * rand::random::<f64>();
+        let y = 10.0 + 15.0 * rand::random::<f64>();
+        let hash = space.encode(x, y);
+        assert!(cells.iter().any(|c| hash.starts_with(c.as_str())));
+    }
+    let inside = space.interior_cover(&room, &options);
+    assert!(!inside.is_empty());
+    for cell in &inside {
+        assert!(room.contains_box(&space.bounding_box(cell)));
+    }
+}
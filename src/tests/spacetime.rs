@@ -0,0 +1,128 @@
+use crate as geohash;
+use crate::spacetime::{self, SpaceTimeBox, TimeRange};
+
+/// A day of seconds since the epoch.
+const DAY: TimeRange = TimeRange {
+    start: 1_600_000_000.0,
+    end: 1_600_086_400.0,
+};
+
+fn random_ping() -> (f64, f64, f64) {
+    (
+        -90.0 + 180.0 * rand::random::<f64>(),
+        -180.0 + 360.0 * rand::random::<f64>(),
+        DAY.start + (DAY.end - DAY.start) * rand::random::<f64>(),
+    )
+}
+
+fn contains(b: &SpaceTimeBox, lat: f64, lng: f64, time: f64) -> bool {
+    b.space.contains(lat, lng) && b.min_time <= time && time <= b.max_time
+}
+
+#[test]
+fn encode() {
+    let key = spacetime::encode(33.0, -117.0, DAY.start, &DAY);
+    assert!(key < 1 << spacetime::MAX_BITS);
+    // The first bits of the key are those of the geohash.
+    let hash = geohash::encode_int_with_precision(33.0, -117.0, 2);
+    assert!(spacetime::encode_with_precision(33.0, -117.0, DAY.end - 1.0, &DAY, 2) == hash as u128);
+    assert!(
+        spacetime::encode_with_precision(33.0, -117.0, DAY.start, &DAY, 3) == (hash as u128) << 1
+    );
+    assert!(
+        spacetime::encode_with_precision(33.0, -117.0, DAY.end - 1.0, &DAY, 3)
+            == (hash as u128) << 1 | 1
+    );
+    // Times outside of the range are clamped.
+    assert!(spacetime::encode(33.0, -117.0, 0.0, &DAY) == key);
+    assert!(spacetime::encode_with_precision(0.0, 0.0, 0.0, &DAY, 0) == 0);
+}
+
+#[test]
+fn bounding_box() {
+    for _ in 0..1000 {
+        let (lat, lng, time) = random_ping();
+        let bits = rand::random::<usize>() % (spacetime::MAX_BITS + 1);
+        let key = spacetime::encode_with_precision(lat, lng, time, &DAY, bits);
+        let b = spacetime::bounding_box(key, bits, &DAY);
+        assert!(contains(&b, lat, lng, time));
+        let (lat_err, lng_err, time_err) = spacetime::error_with_precision(&DAY, bits);
+        assert!((b.space.max_lat - b.space.min_lat - lat_err).abs() < 1e-9);
+        assert!((b.space.max_lng - b.space.min_lng - lng_err).abs() < 1e-9);
+        assert!((b.max_time - b.min_time - time_err).abs() < 1e-6);
+        let (clat, clng, ctime) = spacetime::decode(key, bits, &DAY);
+        assert!(spacetime::encode_with_precision(clat, clng, ctime, &DAY, bits) == key);
+    }
+    assert!((DAY.resolution(96) - 86_400.0 / 4_294_967_296.0).abs() < 1e-12);
+    assert!(DAY.resolution(3) == 43_200.0);
+}
+
+#[test]
+// Every ping inside the box during the times must fall into one of the ranges.
+fn box_to_ranges() {
+    for _ in 0..100 {
+        let (lat, lng, time) = random_ping();
+        let b = SpaceTimeBox {
+            space: geohash::Box {
+                min_lat: lat,
+                max_lat: (lat + 10.0 * rand::random::<f64>()).min(90.0),
+                min_lng: lng,
+                max_lng: (lng + 10.0 * rand::random::<f64>()).min(180.0),
+            },
+            min_time: time,
+            max_time: (time + 3600.0).min(DAY.end),
+        };
+        let max_ranges = 1 + rand::random::<usize>() % 32;
+        let ranges = spacetime::box_to_ranges(&b, &DAY, 60, max_ranges);
+        assert!(!ranges.is_empty() && ranges.len() <= max_ranges);
+        for w in ranges.windows(2) {
+            assert!(w[0].0 <= w[0].1 && w[0].1 + 1 < w[1].0);
+        }
+        for _ in 0..100 {
+            let lat = b.space.min_lat + (b.space.max_lat - b.space.min_lat) * rand::random::<f64>();
+            let lng = b.space.min_lng + (b.space.max_lng - b.space.min_lng) * rand::random::<f64>();
+            let time = b.min_time + (b.max_time - b.min_time) * rand::random::<f64>();
+            let key = spacetime::encode(lat, lng, time, &DAY);
+            assert!(ranges
+                .iter()
+                .any(|&(first, last)| first <= key && key <= last));
+        }
+    }
+    let everything = SpaceTimeBox {
+        space: geohash::Box {
+            min_lat: -90.0,
+            max_lat: 90.0,
+            min_lng: -180.0,
+            max_lng: 180.0,
+        },
+        min_time: DAY.start,
+        max_time: DAY.end,
+    };
+    assert!(spacetime::box_to_ranges(&everything, &DAY, 96, 4) == vec![(0, (1 << 96) - 1)]);
+}
+
+#[test]
+// A box across the antimeridian covers both of its sides.
+fn box_to_ranges_antimeridian() {
+    let b = SpaceTimeBox {
+        space: geohash::Box {
+            min_lat: -10.0,
+            max_lat: 10.0,
+            min_lng: 170.0,
+            max_lng: -170.0,
+        },
+        min_time: DAY.start,
+        max_time: DAY.end,
+    };
+    let ranges = spacetime::box_to_ranges(&b, &DAY, 60, 16);
+    for &lng in &[175.0, -175.0, 180.0, -180.0] {
+        let key = spacetime::encode(0.0, lng, DAY.start + 43200.0, &DAY);
+        assert!(ranges
+            .iter()
+            .any(|&(first, last)| first <= key && key <= last));
+    }
+    let key = spacetime::encode(0.0, 0.0, DAY.start + 43200.0, &DAY);
+    assert!(!ranges
+        .iter()
+        .any(|&(first, last)| first <= key && key <= last));
+}
@@ -21,7 +21,7 @@ impl<'a> Iterator for Iter<'a> {
     fn next(&mut self) -> Option<TestCase> {
         match self.data.find("},\n") {
             Some(index) => {
-                let line = &(&self.data[..index + 1]).trim();
+                let line = &self.data[..index + 1].trim();
                 // println!("-----------\n{}\n----------", line);
                 let case = TestCase {
                     hash_int: line[1..]
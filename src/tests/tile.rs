@@ -0,0 +1,109 @@
+use crate as geohash;
+use crate::tile::{self, Tile};
+
+#[test]
+fn point_to_tile() {
+    assert!(tile::point_to_tile(0.0, 0.0, 0).unwrap() == Tile::new(0, 0, 0));
+    // Tower Bridge, London.
+    assert!(tile::point_to_tile(51.5055, -0.0754, 14).unwrap() == Tile::new(8188, 5448, 14));
+    // Points beyond the edges of the map are clamped.
+    assert!(tile::point_to_tile(90.0, 180.0, 3).unwrap() == Tile::new(7, 0, 3));
+    assert!(tile::point_to_tile(-90.0, -180.0, 3).unwrap() == Tile::new(0, 7, 3));
+    // Columns and rows of deeper zoom levels do not fit a u32.
+    let t = tile::point_to_tile(90.0, 180.0, tile::MAX_ZOOM).unwrap();
+    assert!(t == Tile::new(u32::MAX, 0, 32));
+    assert!(tile::point_to_tile(0.0, 0.0, 33).is_err());
+}
+
+#[test]
+fn bounding_box() {
+    let b = Tile::new(0, 0, 0).bounding_box();
+    assert!((b.max_lat - tile::MAX_LAT).abs() < 1e-9 && (b.min_lat + tile::MAX_LAT).abs() < 1e-9);
+    assert!(b.min_lng == -180.0 && b.max_lng == 180.0);
+    let t = Tile::new(8188, 5448, 14);
+    let (lat, lng) = t.bounding_box().center();
+    assert!(tile::point_to_tile(lat, lng, 14).unwrap() == t);
+}
+
+#[test]
+fn quadkey() {
+    let t = Tile::new(3, 5, 3);
+    assert!(t.quadkey().unwrap() == "213");
+    assert!(Tile::from_quadkey("213") == Ok(t));
+    assert!(Tile::new(0, 0, 0).quadkey().unwrap().is_empty());
+    assert!(Tile::from_quadkey("0124").is_err());
+    // Tiles deeper than 32 levels do not fit a u32 column and row.
+    let deepest = Tile::new(u32::MAX, 0, 32);
+    assert!(Tile::from_quadkey(&deepest.quadkey().unwrap()) == Ok(deepest));
+    assert!(Tile::new(0, 0, 33).quadkey().is_err());
+    assert!(Tile::from_quadkey(&"1".repeat(33)).is_err());
+}
+
+#[test]
+fn int_quadkey() {
+    // The north-west quarter of the world.
+    let (hash, bits) = tile::quadkey_to_int("0").unwrap();
+    let b = geohash::bounding_box_int_with_precision(hash, bits);
+    assert!(b.min_lat == 0.0 && b.max_lat == 90.0 && b.min_lng == -180.0 && b.max_lng == 0.0);
+    for _ in 0..100 {
+        let levels = rand::random::<usize>() % 33;
+        let hash = geohash::shr(rand::random::<u64>(), 64 - 2 * levels);
+        let quadkey = tile::int_to_quadkey(hash, levels).unwrap();
+        assert!(quadkey.len() == levels);
+        assert!(tile::quadkey_to_int(&quadkey) == Ok((hash, 2 * levels)));
+    }
+    // Geohash "s" is the 5 bits 11000, so its first two levels are NE then SW.
+    let hash = geohash::base32::decode(b"s") >> 1;
+    assert!(tile::int_to_quadkey(hash, 2) == Ok("12".to_string()));
+    assert!(tile::quadkey_to_int("x").is_err());
+    assert!(tile::int_to_quadkey(0, 33).is_err());
+    assert!(tile::quadkey_to_int(&"0".repeat(33)).is_err());
+}
+
+#[test]
+fn hash_to_tiles() {
+    // "s" spans 0..45 degrees of latitude and longitude.
+    let tiles = tile::hash_to_tiles("s", 2).unwrap();
+    assert!(tiles == vec![Tile::new(2, 1, 2)]);
+    let tiles = tile::hash_to_tiles("s", 3).unwrap();
+    assert!(tiles == vec![Tile::new(4, 2, 3), Tile::new(4, 3, 3)]);
+    // A cell inside a single tile.
+    let t = tile::point_to_tile(51.5055, -0.0754, 10).unwrap();
+    let hash = geohash::encode_with_precision(51.5055, -0.0754, 7);
+    assert!(tile::hash_to_tiles(&hash, 10).unwrap() == vec![t]);
+    // Cells beyond the edge of the map belong to the edge row.
+    assert!(tile::hash_to_tiles("zzz", 2).unwrap() == vec![Tile::new(3, 0, 2)]);
+    assert!(tile::hash_to_tiles("s", 33).is_err());
+    assert!(tile::int_to_tiles(0, 0, 33).is_err());
+}
+
+#[test]
+// The geohashes of a tile must cover it, and every one must overlap it.
+fn tile_to_hashes() {
+    let t = Tile::new(2, 1, 2);
+    assert!(tile::tile_to_hashes(&t, 1).unwrap() == vec!["s", "t", "u", "v"]);
+    assert!(tile::tile_to_hashes(&t, 13).is_err());
+    assert!(tile::tile_to_ints(&t, 65).is_err());
+    for _ in 0..20 {
+        let z = rand::random::<u32>() % 12;
+        let t = Tile::new(
+            rand::random::<u32>() % (1 << z),
+            rand::random::<u32>() % (1 << z),
+            z,
+        );
+        let b = t.bounding_box();
+        let chars = 1 + (z as usize * 2 / 5);
+        let hashes = tile::tile_to_hashes(&t, chars).unwrap();
+        for hash in &hashes {
+            let c = geohash::bounding_box(hash);
+            assert!(c.min_lat < b.max_lat && b.min_lat < c.max_lat);
+            assert!(c.min_lng < b.max_lng && b.min_lng < c.max_lng);
+        }
+        for _ in 0..20 {
+            let lat = b.min_lat + (b.max_lat - b.min_lat) * rand::random::<f64>();
+            let lng = b.min_lng + (b.max_lng - b.min_lng) * rand::random::<f64>();
+            let hash = geohash::encode_with_precision(lat, lng, chars);
+            assert!(hashes.contains(&hash));
+        }
+    }
+}
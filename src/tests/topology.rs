@@ -0,0 +1,79 @@
+use super::neighbors_test_cases;
+use crate as geohash;
+use crate::topology::{self, CellRelation};
+
+#[test]
+fn ancestor() {
+    assert!(topology::is_ancestor("9q8", "9q8yy"));
+    assert!(topology::is_ancestor("", "9"));
+    assert!(!topology::is_ancestor("9q8yy", "9q8yy"));
+    assert!(!topology::is_ancestor("9q8yy", "9q8"));
+    assert!(!topology::is_ancestor("9q9", "9q8yy"));
+    assert!(topology::is_ancestor_int(0b10, 2, 0b1011, 4));
+    assert!(!topology::is_ancestor_int(0b11, 2, 0b1011, 4));
+    assert!(topology::is_ancestor_int(0, 0, u64::MAX, 64));
+}
+
+#[test]
+// Every neighbor must share an edge or a corner in its direction.
+fn neighbors() {
+    let edges = [
+        (geohash::NORTH, true),
+        (geohash::NORTH_EAST, false),
+        (geohash::EAST, true),
+        (geohash::SOUTH_EAST, false),
+        (geohash::SOUTH, true),
+        (geohash::SOUTH_WEST, false),
+        (geohash::WEST, true),
+        (geohash::NORTH_WEST, false),
+    ];
+    for c in neighbors_test_cases::iter() {
+        for &(dir, edge) in &edges {
+            let hash = &c.hash_str;
+            let n = &c.hash_str_neighbors[dir];
+            assert!(topology::is_adjacent(hash, n));
+            if edge {
+                assert!(topology::shared_edge(hash, n) == Some(dir));
+                assert!(topology::relation(hash, n) == CellRelation::SharesEdge);
+            } else {
+                assert!(topology::shared_edge(hash, n).is_none());
+                assert!(topology::relation(hash, n) == CellRelation::SharesCorner);
+            }
+            let bits = c.hash_int_bit_depth;
+            let n = c.hash_int_neighbors[dir];
+            assert!(topology::is_adjacent_int(c.hash_int, bits, n, bits));
+        }
+    }
+}
+
+#[test]
+fn mixed_precision() {
+    assert!(topology::relation("9q8", "9q8yy") == CellRelation::Contains);
+    assert!(topology::relation("9q8yy", "9q8") == CellRelation::Within);
+    assert!(topology::relation("9q8yy", "9q8yy") == CellRelation::Equal);
+    // "9q8z" lies north of "9q8y"; its south-western child touches the edge.
+    assert!(topology::shared_edge("9q8y", "9q8z0") == Some(geohash::NORTH));
+    assert!(topology::shared_edge("9q8z0", "9q8y") == Some(geohash::SOUTH));
+    // A child in the middle of "9q8z" does not touch "9q8y".
+    assert!(topology::relation("9q8y", "9q8zk") == CellRelation::Disjoint);
+    assert!(!topology::is_adjacent("9q8y", "9q8zk"));
+    // The corner child of the north-eastern neighbor.
+    let ne = geohash::neighbor("9q8y", geohash::NORTH_EAST);
+    assert!(topology::relation("9q8y", &format!("{}0", ne)) == CellRelation::SharesCorner);
+    assert!(topology::relation("9q8y", &format!("{}1", ne)) == CellRelation::Disjoint);
+}
+
+#[test]
+fn antimeridian() {
+    let west = geohash::encode_with_precision(10.0, 179.99, 4);
+    let east = geohash::encode_with_precision(10.0, -179.99, 6);
+    assert!(topology::shared_edge(&west, &east) == Some(geohash::EAST));
+    assert!(topology::shared_edge(&east, &west) == Some(geohash::WEST));
+    let b = geohash::bounding_box(&west);
+    let below = geohash::encode_with_precision(b.min_lat - 0.01, -179.99, 4);
+    assert!(topology::relation(&west, &below) == CellRelation::SharesCorner);
+    // The poles do not wrap.
+    let north = geohash::encode_with_precision(89.99, 0.0, 3);
+    let south = geohash::encode_with_precision(-89.99, 0.0, 3);
+    assert!(topology::relation(&north, &south) == CellRelation::Disjoint);
+}
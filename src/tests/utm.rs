@@ -0,0 +1,69 @@
+use crate::utm::{Hemisphere, Utm};
+
+#[test]
+fn forward() {
+    // The Eiffel Tower.
+    let utm = Utm::from_lat_lng(48.8583, 2.2945);
+    assert!(utm.zone == 31 && utm.hemisphere == Hemisphere::North);
+    assert!((utm.easting - 448_251.898).abs() < 1e-3, "{:?}", utm);
+    assert!((utm.northing - 5_411_943.794).abs() < 1e-3, "{:?}", utm);
+    let utm = Utm::from_lat_lng(0.0, 0.0);
+    assert!(utm.zone == 31 && (utm.easting - 166_021.443).abs() < 1e-3 && utm.northing == 0.0);
+    let utm = Utm::from_lat_lng(-33.8568, 151.2153);
+    assert!(utm.zone == 56 && utm.hemisphere == Hemisphere::South);
+    assert!(
+        (utm.easting - 334_900.570).abs() < 1e-3 && (utm.northing - 6_252_288.753).abs() < 1e-3,
+        "{:?}",
+        utm
+    );
+}
+
+#[test]
+fn zones() {
+    assert!(Utm::from_lat_lng(60.0, 5.0).zone == 32);
+    assert!(Utm::from_lat_lng(60.0, 2.0).zone == 31);
+    assert!(Utm::from_lat_lng(78.0, 10.0).zone == 33);
+    assert!(Utm::from_lat_lng(78.0, 40.0).zone == 37);
+    assert!(Utm::from_lat_lng(10.0, 180.0).zone == 1);
+    assert!(Utm::from_lat_lng(10.0, 179.9).zone == 60);
+    assert!(Utm::from_lat_lng(84.0, 0.0).zone == 0);
+    assert!(Utm::from_lat_lng(-80.0, 0.0).zone == 31);
+    assert!(Utm::from_lat_lng(-80.1, 0.0).zone == 0);
+}
+
+#[test]
+fn poles() {
+    let utm = Utm::from_lat_lng(90.0, 0.0);
+    assert!(utm.zone == 0 && utm.easting == 2_000_000.0 && utm.northing == 2_000_000.0);
+    // 85N on the prime meridian lies south of the pole on the grid.
+    let utm = Utm::from_lat_lng(85.0, 0.0);
+    assert!((utm.easting - 2_000_000.0).abs() < 1e-6);
+    assert!((utm.northing - 1_444_542.608).abs() < 1e-2, "{:?}", utm);
+    let utm = Utm::from_lat_lng(-85.0, 0.0);
+    assert!((utm.northing - 2_555_457.392).abs() < 1e-2, "{:?}", utm);
+}
+
+#[test]
+// Converting to UTM or UPS and back must give the same point.
+fn round_trip() {
+    for _ in 0..1000 {
+        let lat = -90.0 + 180.0 * rand::random::<f64>();
+        let lng = -180.0 + 360.0 * rand::random::<f64>();
+        let utm = Utm::from_lat_lng(lat, lng);
+        let (lat2, lng2) = utm.to_lat_lng();
+        assert!((lat - lat2).abs() < 1e-9, "{} {} {:?}", lat, lng, utm);
+        assert!(
+            lat.abs() > 89.9999 || (lng - lng2).abs() < 1e-9,
+            "{} {} {:?}",
+            lat,
+            lng,
+            utm
+        );
+        // Neighboring zones work too.
+        if utm.zone > 1 && utm.zone < 60 {
+            let utm = Utm::from_lat_lng_in_zone(lat, lng, utm.zone + 1);
+            let (lat2, lng2) = utm.to_lat_lng();
+            assert!((lat - lat2).abs() < 1e-9 && (lng - lng2).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Conversions between geohashes, Web Mercator XYZ tiles and quadkeys.
+
+use crate::{base32, base32_string, bounding_box_int_with_precision, box_cells, Box};
+use std::f64::consts::PI;
+
+/// MAX_LAT is the latitude of the northern edge of the Web Mercator world.
+/// Points further north or south are clamped to it.
+pub const MAX_LAT: f64 = 85.051_128_779_806_59;
+
+/// MAX_ZOOM is the deepest zoom level, whose columns and rows still fit a u32.
+pub const MAX_ZOOM: u32 = 32;
+
+/// Tile is a Web Mercator map tile at zoom level z. Columns x grow eastward
+/// from the antimeridian and rows y grow southward from MAX_LAT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+impl Tile {
+    /// new returns the tile at column x and row y of zoom level z.
+    pub fn new(x: u32, y: u32, z: u32) -> Tile {
+        Tile { x, y, z }
+    }
+
+    /// bounding_box returns the region covered by the tile.
+    pub fn bounding_box(&self) -> Box {
+        let n = (self.z as f64).exp2();
+        Box {
+            min_lat: row_lat(self.y as f64 + 1.0, n),
+            max_lat: row_lat(self.y as f64, n),
+            min_lng: self.x as f64 / n * 360.0 - 180.0,
+            max_lng: (self.x as f64 + 1.0) / n * 360.0 - 180.0,
+        }
+    }
+
+    /// quadkey returns the Bing Maps quadkey of the tile, one digit per zoom
+    /// level. Zoom levels above MAX_ZOOM have no quadkey.
+    pub fn quadkey(&self) -> Result<String, String> {
+        check_zoom(self.z)?;
+        Ok((1..=self.z)
+            .rev()
+            .map(|i| {
+                let digit = (self.x >> (i - 1)) & 1 | ((self.y >> (i - 1)) & 1) << 1;
+                (b'0' + digit as u8) as char
+            })
+            .collect())
+    }
+
+    /// from_quadkey returns the tile of the Bing Maps quadkey.
+    pub fn from_quadkey(quadkey: &str) -> Result<Tile, String> {
+        if quadkey.len() > MAX_ZOOM as usize {
+            return Err(format!("quadkey too long: {}", quadkey.len()));
+        }
+        let mut tile = Tile::new(0, 0, 0);
+        for c in quadkey.bytes() {
+            let digit = quadkey_digit(c)?;
+            tile.x = tile.x << 1 | digit & 1;
+            tile.y = tile.y << 1 | digit >> 1;
+            tile.z += 1;
+        }
+        Ok(tile)
+    }
+}
+
+/// point_to_tile returns the tile at the zoom level (max MAX_ZOOM) that
+/// contains the point (lat, lng).
+pub fn point_to_tile(lat: f64, lng: f64, zoom: u32) -> Result<Tile, String> {
+    check_zoom(zoom)?;
+    let n = (zoom as f64).exp2();
+    Ok(Tile::new(column(lng, n), row(lat, n), zoom))
+}
+
+/// hash_to_tiles returns the tiles at the zoom level (max MAX_ZOOM) that cover
+/// the cell of the string geohash, in row-major order.
+pub fn hash_to_tiles(hash: &str, zoom: u32) -> Result<Vec<Tile>, String> {
+    int_to_tiles(base32::decode(hash.as_bytes()), 5 * hash.len(), zoom)
+}
+
+/// int_to_tiles returns the tiles at the zoom level (max MAX_ZOOM) that cover
+/// the cell of the integer geohash with bits of precision, in row-major order.
+pub fn int_to_tiles(hash: u64, bits: usize, zoom: u32) -> Result<Vec<Tile>, String> {
+    check_zoom(zoom)?;
+    let b = bounding_box_int_with_precision(hash, bits);
+    let n = (zoom as f64).exp2();
+    // Tiles that only touch the cell along an edge are left out.
+    let (x0, x1) = (
+        column(b.min_lng, n),
+        last_index((b.max_lng + 180.0) / 360.0 * n, n),
+    );
+    let (y0, y1) = (row(b.max_lat, n), last_index(mercator_y(b.min_lat) * n, n));
+    let mut tiles = Vec::new();
+    for y in y0..=y1.max(y0) {
+        for x in x0..=x1.max(x0) {
+            tiles.push(Tile::new(x, y, zoom));
+        }
+    }
+    Ok(tiles)
+}
+
+/// tile_to_hashes returns the string geohashes with chars characters (max 12)
+/// that cover the tile, in row-major order from the south-west.
+pub fn tile_to_hashes(tile: &Tile, chars: usize) -> Result<Vec<String>, String> {
+    if chars > 12 {
+        return Err(format!("too many characters: {}", chars));
+    }
+    Ok(tile_to_ints(tile, 5 * chars)?
+        .into_iter()
+        .map(|hash| base32_string(hash, chars))
+        .collect())
+}
+
+/// tile_to_ints returns the integer geohashes with bits of precision (max 64)
+/// that cover the tile, in row-major order from the south-west.
+pub fn tile_to_ints(tile: &Tile, bits: usize) -> Result<Vec<u64>, String> {
+    if bits > 64 {
+        return Err(format!("too many bits: {}", bits));
+    }
+    Ok(box_cells(&tile.bounding_box(), bits))
+}
+
+/// int_to_quadkey returns the quadkey of the integer geohash with 2 * levels
+/// bits of precision, in the equirectangular quadtree of encode_int. Like Bing
+/// Maps quadkeys, the digits 0, 1, 2 and 3 stand for the north-west,
+/// north-east, south-west and south-east quarters of a cell. A 64-bit hash has
+/// at most 32 levels.
+pub fn int_to_quadkey(hash: u64, levels: usize) -> Result<String, String> {
+    if levels > 32 {
+        return Err(format!("too many levels: {}", levels));
+    }
+    Ok((0..levels)
+        .rev()
+        .map(|i| {
+            let lng = (hash >> (2 * i + 1)) & 1;
+            let lat = (hash >> (2 * i)) & 1;
+            (b'0' + (lng | (1 - lat) << 1) as u8) as char
+        })
+        .collect())
+}
+
+/// quadkey_to_int returns the integer geohash and its bits of precision of the
+/// quadkey in the equirectangular quadtree of encode_int.
+pub fn quadkey_to_int(quadkey: &str) -> Result<(u64, usize), String> {
+    if quadkey.len() > 32 {
+        return Err(format!("quadkey too long: {}", quadkey.len()));
+    }
+    let mut hash = 0;
+    for c in quadkey.bytes() {
+        let digit = quadkey_digit(c)? as u64;
+        hash = hash << 2 | (digit & 1) << 1 | (1 - (digit >> 1));
+    }
+    Ok((hash, 2 * quadkey.len()))
+}
+
+fn check_zoom(zoom: u32) -> Result<(), String> {
+    if zoom > MAX_ZOOM {
+        return Err(format!("zoom level too high: {}", zoom));
+    }
+    Ok(())
+}
+
+fn quadkey_digit(c: u8) -> Result<u32, String> {
+    match c {
+        b'0'..=b'3' => Ok((c - b'0') as u32),
+        _ => Err(format!("invalid quadkey digit: {}", c as char)),
+    }
+}
+
+/// mercator_y returns the position of the latitude from the top of the Web
+/// Mercator world, between 0 and 1.
+fn mercator_y(lat: f64) -> f64 {
+    let lat = lat.clamp(-MAX_LAT, MAX_LAT).to_radians();
+    (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0
+}
+
+/// row_lat returns the latitude of the top of row y out of n.
+fn row_lat(y: f64, n: f64) -> f64 {
+    (PI * (1.0 - 2.0 * y / n)).sinh().atan().to_degrees()
+}
+
+fn column(lng: f64, n: f64) -> u32 {
+    ((lng + 180.0) / 360.0 * n).floor().clamp(0.0, n - 1.0) as u32
+}
+
+fn row(lat: f64, n: f64) -> u32 {
+    (mercator_y(lat) * n).floor().clamp(0.0, n - 1.0) as u32
+}
+
+/// last_index returns the index of the last tile before the position, which
+/// is the end of a span.
+fn last_index(pos: f64, n: f64) -> u32 {
+    (pos.ceil() - 1.0).clamp(0.0, n - 1.0) as u32
+}
@@ -0,0 +1,139 @@
+//! Exact topological relations between geohash cells of any precision.
+
+use crate::{base32, cell_int_range, Direction, EAST, NORTH, SOUTH, WEST};
+
+/// CellRelation is the topological relation of one cell to another. Geohash
+/// cells are either nested or have disjoint interiors, so two cells overlap
+/// exactly when they are equal or one contains the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellRelation {
+    /// Equal cells cover the same region.
+    Equal,
+    /// Contains means the first cell contains the second.
+    Contains,
+    /// Within means the first cell lies within the second.
+    Within,
+    /// SharesEdge means the cells touch along part of an edge.
+    SharesEdge,
+    /// SharesCorner means the cells touch only at a corner.
+    SharesCorner,
+    /// Disjoint cells have no point in common.
+    Disjoint,
+}
+
+/// Span of the 32-bit latitude and longitude integers.
+const SPAN: u64 = 1 << 32;
+
+/// Cell is the half-open ranges of latitude and longitude integers of a cell.
+struct Cell {
+    lat: (u64, u64),
+    lng: (u64, u64),
+}
+
+impl Cell {
+    fn new(hash: u64, bits: usize) -> Cell {
+        let (lat, lng) = cell_int_range(hash, bits);
+        Cell {
+            lat: (lat.0 as u64, lat.1 as u64 + 1),
+            lng: (lng.0 as u64, lng.1 as u64 + 1),
+        }
+    }
+}
+
+/// is_ancestor decides whether the cell of the string geohash a strictly
+/// contains the cell of b.
+pub fn is_ancestor(a: &str, b: &str) -> bool {
+    a.len() < b.len() && b.starts_with(a)
+}
+
+/// is_ancestor_int decides whether the cell of the integer geohash a with
+/// a_bits of precision strictly contains the cell of b with b_bits.
+pub fn is_ancestor_int(a: u64, a_bits: usize, b: u64, b_bits: usize) -> bool {
+    a_bits < b_bits && crate::shr(b, b_bits - a_bits) == a
+}
+
+/// is_adjacent decides whether the cells of the string geohashes a and b touch
+/// along an edge or at a corner without overlapping.
+pub fn is_adjacent(a: &str, b: &str) -> bool {
+    is_adjacent_int(decode(a), 5 * a.len(), decode(b), 5 * b.len())
+}
+
+/// is_adjacent_int decides whether the cells of the integer geohashes a and b
+/// touch along an edge or at a corner without overlapping.
+pub fn is_adjacent_int(a: u64, a_bits: usize, b: u64, b_bits: usize) -> bool {
+    matches!(
+        relation_int(a, a_bits, b, b_bits),
+        CellRelation::SharesEdge | CellRelation::SharesCorner
+    )
+}
+
+/// shared_edge returns the direction from the cell of the string geohash a to
+/// the cell of b, when they touch along part of an edge. It is one of NORTH,
+/// EAST, SOUTH and WEST.
+pub fn shared_edge(a: &str, b: &str) -> Option<Direction> {
+    shared_edge_int(decode(a), 5 * a.len(), decode(b), 5 * b.len())
+}
+
+/// shared_edge_int returns the direction from the cell of the integer geohash
+/// a to the cell of b, when they touch along part of an edge.
+pub fn shared_edge_int(a: u64, a_bits: usize, b: u64, b_bits: usize) -> Option<Direction> {
+    let (a, b) = (Cell::new(a, a_bits), Cell::new(b, b_bits));
+    if overlaps(a.lng, b.lng) {
+        if a.lat.1 == b.lat.0 {
+            return Some(NORTH);
+        }
+        if b.lat.1 == a.lat.0 {
+            return Some(SOUTH);
+        }
+    }
+    if overlaps(a.lat, b.lat) {
+        if a.lng.1 % SPAN == b.lng.0 {
+            return Some(EAST);
+        }
+        if b.lng.1 % SPAN == a.lng.0 {
+            return Some(WEST);
+        }
+    }
+    None
+}
+
+/// relation returns the topological relation of the cell of the string
+/// geohash a to the cell of b.
+pub fn relation(a: &str, b: &str) -> CellRelation {
+    relation_int(decode(a), 5 * a.len(), decode(b), 5 * b.len())
+}
+
+/// relation_int returns the topological relation of the cell of the integer
+/// geohash a with a_bits of precision to the cell of b with b_bits.
+pub fn relation_int(a: u64, a_bits: usize, b: u64, b_bits: usize) -> CellRelation {
+    if a_bits == b_bits && a == b {
+        return CellRelation::Equal;
+    }
+    if is_ancestor_int(a, a_bits, b, b_bits) {
+        return CellRelation::Contains;
+    }
+    if is_ancestor_int(b, b_bits, a, a_bits) {
+        return CellRelation::Within;
+    }
+    if shared_edge_int(a, a_bits, b, b_bits).is_some() {
+        return CellRelation::SharesEdge;
+    }
+    let (a, b) = (Cell::new(a, a_bits), Cell::new(b, b_bits));
+    let lat_touch = a.lat.1 == b.lat.0 || b.lat.1 == a.lat.0;
+    let lng_touch = a.lng.1 % SPAN == b.lng.0 || b.lng.1 % SPAN == a.lng.0;
+    if lat_touch && lng_touch {
+        CellRelation::SharesCorner
+    } else {
+        CellRelation::Disjoint
+    }
+}
+
+/// overlaps decides whether two half-open ranges have a part of positive
+/// length in common.
+fn overlaps(a: (u64, u64), b: (u64, u64)) -> bool {
+    a.0 < b.1 && b.0 < a.1
+}
+
+fn decode(hash: &str) -> u64 {
+    base32::decode(hash.as_bytes())
+}
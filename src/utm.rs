@@ -0,0 +1,254 @@
+//! Universal Transverse Mercator (UTM) and Universal Polar Stereographic (UPS)
+//! coordinates on the WGS84 ellipsoid.
+
+use crate::distance::normalize_lng;
+use crate::geodesic::WGS84;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+/// Hemisphere is the half of the earth a UTM or UPS coordinate belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hemisphere {
+    North,
+    South,
+}
+
+/// Utm is a position in meters in a UTM zone, numbered 1 to 60, or in a UPS
+/// polar region when the zone is 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Utm {
+    pub zone: u8,
+    pub hemisphere: Hemisphere,
+    pub easting: f64,
+    pub northing: f64,
+}
+
+/// Scale factors on the central meridian of UTM zones and at the poles of UPS.
+const UTM_K0: f64 = 0.9996;
+const UPS_K0: f64 = 0.994;
+/// False eastings and northings in meters.
+const UTM_EASTING: f64 = 500_000.0;
+const UTM_SOUTH_NORTHING: f64 = 10_000_000.0;
+const UPS_OFFSET: f64 = 2_000_000.0;
+/// UTM covers the latitudes from MIN_UTM_LAT up to MAX_UTM_LAT, and UPS the
+/// rest.
+pub const MIN_UTM_LAT: f64 = -80.0;
+pub const MAX_UTM_LAT: f64 = 84.0;
+
+impl Utm {
+    /// from_lat_lng returns the UTM coordinate of the point (lat, lng) in its
+    /// standard zone, including the exceptions around Norway and Svalbard, or
+    /// its UPS coordinate near the poles.
+    pub fn from_lat_lng(lat: f64, lng: f64) -> Utm {
+        let lng = normalize_lng(lng);
+        if !(MIN_UTM_LAT..MAX_UTM_LAT).contains(&lat) {
+            return ups_forward(lat, lng);
+        }
+        Utm::from_lat_lng_in_zone(lat, lng, standard_zone(lat, lng))
+    }
+
+    /// from_lat_lng_in_zone returns the UTM coordinate of the point (lat, lng)
+    /// in the zone, which may be a neighbor of its standard zone.
+    pub fn from_lat_lng_in_zone(lat: f64, lng: f64, zone: u8) -> Utm {
+        let lng = normalize_lng(lng - central_meridian(zone));
+        let (x, y) = transverse_mercator(lat.to_radians(), lng.to_radians());
+        let hemisphere = if lat < 0.0 {
+            Hemisphere::South
+        } else {
+            Hemisphere::North
+        };
+        Utm {
+            zone,
+            hemisphere,
+            easting: UTM_EASTING + UTM_K0 * x,
+            northing: match hemisphere {
+                Hemisphere::North => UTM_K0 * y,
+                Hemisphere::South => UTM_SOUTH_NORTHING + UTM_K0 * y,
+            },
+        }
+    }
+
+    /// to_lat_lng returns the point (lat, lng) of the coordinate.
+    pub fn to_lat_lng(&self) -> (f64, f64) {
+        if self.zone == 0 {
+            return ups_inverse(self);
+        }
+        let x = (self.easting - UTM_EASTING) / UTM_K0;
+        let y = match self.hemisphere {
+            Hemisphere::North => self.northing,
+            Hemisphere::South => self.northing - UTM_SOUTH_NORTHING,
+        } / UTM_K0;
+        let (lat, lng) = inverse_transverse_mercator(x, y);
+        (
+            lat.to_degrees(),
+            normalize_lng(lng.to_degrees() + central_meridian(self.zone)),
+        )
+    }
+}
+
+/// standard_zone returns the UTM zone of the point, between 1 and 60.
+fn standard_zone(lat: f64, lng: f64) -> u8 {
+    if (56.0..64.0).contains(&lat) && (3.0..12.0).contains(&lng) {
+        return 32;
+    }
+    if (72.0..84.0).contains(&lat) && (0.0..42.0).contains(&lng) {
+        return match lng {
+            _ if lng < 9.0 => 31,
+            _ if lng < 21.0 => 33,
+            _ if lng < 33.0 => 35,
+            _ => 37,
+        };
+    }
+    (((lng + 180.0) / 6.0).floor() as u8 % 60) + 1
+}
+
+fn central_meridian(zone: u8) -> f64 {
+    6.0 * zone as f64 - 183.0
+}
+
+/// third_flattening returns n = f / (2 - f), in which the series of the
+/// transverse Mercator projection are expanded.
+fn third_flattening() -> f64 {
+    WGS84.f / (2.0 - WGS84.f)
+}
+
+/// rectifying_radius returns the radius A of the sphere with the same meridian
+/// length as the ellipsoid.
+fn rectifying_radius(n: f64) -> f64 {
+    let n2 = n * n;
+    WGS84.a / (1.0 + n) * (1.0 + n2 / 4.0 + n2 * n2 / 64.0 + n2 * n2 * n2 / 256.0)
+}
+
+/// conformal_tan returns the tangent of the conformal latitude for the tangent
+/// of the latitude.
+fn conformal_tan(tau: f64, e: f64) -> f64 {
+    let sigma = (e * (e * tau / tau.hypot(1.0)).atanh()).sinh();
+    tau * sigma.hypot(1.0) - sigma * tau.hypot(1.0)
+}
+
+/// transverse_mercator projects the point (lat, lng) in radians, with the
+/// longitude relative to the central meridian, to unscaled meters using
+/// Krüger's series to the sixth order in n.
+fn transverse_mercator(lat: f64, lng: f64) -> (f64, f64) {
+    let n = third_flattening();
+    let e = WGS84.e2().sqrt();
+    let alpha = [
+        n / 2.0 - 2.0 / 3.0 * n.powi(2) + 5.0 / 16.0 * n.powi(3) + 41.0 / 180.0 * n.powi(4)
+            - 127.0 / 288.0 * n.powi(5)
+            + 7891.0 / 37800.0 * n.powi(6),
+        13.0 / 48.0 * n.powi(2) - 3.0 / 5.0 * n.powi(3)
+            + 557.0 / 1440.0 * n.powi(4)
+            + 281.0 / 630.0 * n.powi(5)
+            - 1983433.0 / 1935360.0 * n.powi(6),
+        61.0 / 240.0 * n.powi(3) - 103.0 / 140.0 * n.powi(4)
+            + 15061.0 / 26880.0 * n.powi(5)
+            + 167603.0 / 181440.0 * n.powi(6),
+        49561.0 / 161280.0 * n.powi(4) - 179.0 / 168.0 * n.powi(5)
+            + 6601661.0 / 7257600.0 * n.powi(6),
+        34729.0 / 80640.0 * n.powi(5) - 3418889.0 / 1995840.0 * n.powi(6),
+        212378941.0 / 319334400.0 * n.powi(6),
+    ];
+    let tau = conformal_tan(lat.tan(), e);
+    let xi0 = tau.atan2(lng.cos());
+    let eta0 = (lng.sin() / tau.hypot(lng.cos())).asinh();
+    let (mut xi, mut eta) = (xi0, eta0);
+    for (j, a) in alpha.iter().enumerate() {
+        let k = 2.0 * (j + 1) as f64;
+        xi += a * (k * xi0).sin() * (k * eta0).cosh();
+        eta += a * (k * xi0).cos() * (k * eta0).sinh();
+    }
+    let r = rectifying_radius(n);
+    (r * eta, r * xi)
+}
+
+/// inverse_transverse_mercator returns the point (lat, lng) in radians of the
+/// unscaled position, with the longitude relative to the central meridian.
+fn inverse_transverse_mercator(x: f64, y: f64) -> (f64, f64) {
+    let n = third_flattening();
+    let e2 = WGS84.e2();
+    let e = e2.sqrt();
+    let beta = [
+        n / 2.0 - 2.0 / 3.0 * n.powi(2) + 37.0 / 96.0 * n.powi(3)
+            - 1.0 / 360.0 * n.powi(4)
+            - 81.0 / 512.0 * n.powi(5)
+            + 96199.0 / 604800.0 * n.powi(6),
+        1.0 / 48.0 * n.powi(2) + 1.0 / 15.0 * n.powi(3) - 437.0 / 1440.0 * n.powi(4)
+            + 46.0 / 105.0 * n.powi(5)
+            - 1118711.0 / 3870720.0 * n.powi(6),
+        17.0 / 480.0 * n.powi(3) - 37.0 / 840.0 * n.powi(4) - 209.0 / 4480.0 * n.powi(5)
+            + 5569.0 / 90720.0 * n.powi(6),
+        4397.0 / 161280.0 * n.powi(4) - 11.0 / 504.0 * n.powi(5) - 830251.0 / 7257600.0 * n.powi(6),
+        4583.0 / 161280.0 * n.powi(5) - 108847.0 / 3991680.0 * n.powi(6),
+        20648693.0 / 638668800.0 * n.powi(6),
+    ];
+    let r = rectifying_radius(n);
+    let (xi, eta) = (y / r, x / r);
+    let (mut xi0, mut eta0) = (xi, eta);
+    for (j, b) in beta.iter().enumerate() {
+        let k = 2.0 * (j + 1) as f64;
+        xi0 -= b * (k * xi).sin() * (k * eta).cosh();
+        eta0 -= b * (k * xi).cos() * (k * eta).sinh();
+    }
+    let tau0 = xi0.sin() / eta0.sinh().hypot(xi0.cos());
+    // Newton's method for the tangent of the latitude.
+    let mut tau = tau0;
+    for _ in 0..10 {
+        let tau_i = conformal_tan(tau, e);
+        let delta = (tau0 - tau_i) / tau_i.hypot(1.0) * (1.0 + (1.0 - e2) * tau * tau)
+            / ((1.0 - e2) * tau.hypot(1.0));
+        tau += delta;
+        if delta.abs() < 1e-12 {
+            break;
+        }
+    }
+    (tau.atan(), eta0.sinh().atan2(xi0.cos()))
+}
+
+/// ups_scale returns the factor turning the conformal colatitude term t into
+/// the distance from the pole in meters.
+fn ups_scale(e: f64) -> f64 {
+    2.0 * WGS84.a * UPS_K0 / ((1.0 + e).powf(1.0 + e) * (1.0 - e).powf(1.0 - e)).sqrt()
+}
+
+/// ups_forward returns the UPS coordinate of the point (lat, lng) in degrees.
+fn ups_forward(lat: f64, lng: f64) -> Utm {
+    let e = WGS84.e2().sqrt();
+    let north = lat >= 0.0;
+    let phi = lat.abs().to_radians();
+    let lambda = lng.to_radians();
+    let t = (FRAC_PI_4 - phi / 2.0).tan()
+        / ((1.0 - e * phi.sin()) / (1.0 + e * phi.sin())).powf(e / 2.0);
+    let rho = ups_scale(e) * t;
+    let (hemisphere, northing) = if north {
+        (Hemisphere::North, UPS_OFFSET - rho * lambda.cos())
+    } else {
+        (Hemisphere::South, UPS_OFFSET + rho * lambda.cos())
+    };
+    Utm {
+        zone: 0,
+        hemisphere,
+        easting: UPS_OFFSET + rho * lambda.sin(),
+        northing,
+    }
+}
+
+/// ups_inverse returns the point (lat, lng) in degrees of the UPS coordinate.
+fn ups_inverse(utm: &Utm) -> (f64, f64) {
+    let e = WGS84.e2().sqrt();
+    let dx = utm.easting - UPS_OFFSET;
+    let dy = utm.northing - UPS_OFFSET;
+    let t = dx.hypot(dy) / ups_scale(e);
+    let mut phi = FRAC_PI_2 - 2.0 * t.atan();
+    for _ in 0..20 {
+        let next = FRAC_PI_2
+            - 2.0 * (t * ((1.0 - e * phi.sin()) / (1.0 + e * phi.sin())).powf(e / 2.0)).atan();
+        let done = (next - phi).abs() < 1e-14;
+        phi = next;
+        if done {
+            break;
+        }
+    }
+    match utm.hemisphere {
+        Hemisphere::North => (phi.to_degrees(), dx.atan2(-dy).to_degrees()),
+        Hemisphere::South => (-phi.to_degrees(), dx.atan2(dy).to_degrees()),
+    }
+}